@@ -0,0 +1,96 @@
+//! Common functionality of the games launcher API
+
+use std::time::Duration;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use thiserror::Error;
+
+use crate::disk_cache::DiskCache;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    /// API reported that the service is temporarily unavailable
+    #[error("API service is unavailable: {message} (retcode {retcode})")]
+    ServiceUnavailable {
+        retcode: i32,
+        message: String
+    }
+}
+
+/// Games launcher API response
+pub(crate) trait GamesResponse: DeserializeOwned {
+    type Package;
+
+    fn retcode(&self) -> i32;
+    fn message(&self) -> &str;
+
+    /// Get list of games packages. `None` if the API returned no data
+    fn into_packages(self) -> Option<Vec<Self::Package>>;
+}
+
+#[tracing::instrument(level = "trace", skip(filter))]
+/// Fetch API and find the game package using the filter
+///
+/// Responses registered in `MockApi` are used instead of sending requests
+pub(crate) fn request<T: GamesResponse>(uri: &str, timeout: Option<Duration>, filter: impl Fn(&T::Package) -> bool) -> anyhow::Result<T::Package> {
+    #[cfg(any(test, feature = "test-utils"))]
+    if let Some(response) = crate::testing::MockApi::get(uri) {
+        return find_game(serde_json::from_str::<T>(&response)?, filter);
+    }
+
+    let schema: T = crate::request(minreq::get(uri))
+        .with_timeout(crate::requests_timeout(timeout))
+        .send()?.json()?;
+
+    find_game(schema, filter)
+}
+
+#[tracing::instrument(level = "trace", skip(cache, filter), fields(path = ?cache.path()))]
+/// Fetch API using the disk cache and find the game package using the filter
+///
+/// Cache is removed if it contains invalid response,
+/// so the next call will request the API again
+pub(crate) fn request_with_disk_cache<T>(uri: &str, cache: &DiskCache<T>, timeout: Option<Duration>, filter: impl Fn(&T::Package) -> bool) -> anyhow::Result<T::Package>
+where
+    T: GamesResponse + Serialize
+{
+    let schema = cache.get(uri, crate::requests_timeout(timeout))?;
+
+    let result = find_game(schema, filter);
+
+    if result.is_err() {
+        #[allow(unused_must_use)] {
+            std::fs::remove_file(cache.path());
+        }
+    }
+
+    result
+}
+
+/// Find the game in the API response
+fn find_game<T: GamesResponse>(schema: T, filter: impl Fn(&T::Package) -> bool) -> anyhow::Result<T::Package> {
+    let retcode = schema.retcode();
+
+    if retcode != 0 {
+        tracing::warn!(retcode, message = schema.message(), "API returned non-zero retcode");
+
+        if matches!(retcode, 1 | -1) {
+            return Err(ApiError::ServiceUnavailable {
+                retcode,
+                message: schema.message().to_string()
+            }.into());
+        }
+    }
+
+    let message = schema.message().to_string();
+
+    let Some(packages) = schema.into_packages() else {
+        anyhow::bail!("API returned no data: {message}");
+    };
+
+    packages.into_iter()
+        .find(filter)
+        .ok_or_else(|| anyhow::anyhow!("Failed to find the game in the API"))
+}
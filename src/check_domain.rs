@@ -1,3 +1,6 @@
+use std::time::Duration;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
 /// Check whether given domain name is resolvable
 /// 
 /// Timeout is optional amount of seconds
@@ -11,3 +14,213 @@ pub fn available<T: AsRef<str> + std::fmt::Debug>(domain: T) -> anyhow::Result<b
 
     Ok(false)
 }
+
+/// Check whether given domain name is resolvable within the timeout
+///
+/// Return `Ok(false)` if the lookup didn't finish in time
+#[tracing::instrument(level = "trace")]
+pub fn available_with_timeout(domain: &str, timeout: Duration) -> anyhow::Result<bool> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    let domain = domain.to_string();
+
+    // The lookup can't be cancelled, so the thread is left
+    // running in background if it doesn't finish in time
+    std::thread::spawn(move || {
+        sender.send(available(domain)).ok();
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+
+        Err(_) => {
+            tracing::warn!("Domain lookup timed out");
+
+            Ok(false)
+        }
+    }
+}
+
+/// Check whether given domain names are resolvable in parallel
+///
+/// Results are returned in the same order as the input domains
+#[tracing::instrument(level = "trace")]
+pub fn available_bulk<T: AsRef<str> + Sync + std::fmt::Debug>(domains: &[T]) -> Vec<(String, anyhow::Result<bool>)> {
+    std::thread::scope(|scope| {
+        let handles = domains.iter()
+            .map(|domain| scope.spawn(move || available(domain.as_ref())))
+            .collect::<Vec<_>>();
+
+        domains.iter()
+            .zip(handles)
+            .map(|(domain, handle)| {
+                let result = handle.join()
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("Domain lookup thread panicked")));
+
+                (domain.as_ref().to_string(), result)
+            })
+            .collect()
+    })
+}
+
+/// Check whether given domain name is resolvable using DNS-over-HTTPS server
+///
+/// ```no_run
+/// use anime_game_core::check_domain;
+///
+/// if check_domain::available_doh("example.com", "https://1.1.1.1/dns-query").unwrap() {
+///     println!("Domain is available");
+/// }
+/// ```
+#[tracing::instrument(level = "trace")]
+pub fn available_doh(domain: &str, doh_url: &str) -> anyhow::Result<bool> {
+    // A and AAAA records
+    for record_type in [1, 28] {
+        // https://datatracker.ietf.org/doc/html/rfc8484#section-4.1
+        let response = crate::request(minreq::post(doh_url))
+            .with_header("content-type", "application/dns-message")
+            .with_header("accept", "application/dns-message")
+            .with_body(encode_dns_query(domain, record_type)?)
+            .with_timeout(crate::requests_timeout(None))
+            .send()?;
+
+        if response.status_code != 200 {
+            anyhow::bail!("DoH server returned status code {}", response.status_code);
+        }
+
+        for ip in parse_dns_answers(response.as_bytes())? {
+            if !ip.is_loopback() && !ip.is_unspecified() {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Encode DNS query message in the wire format
+///
+/// https://datatracker.ietf.org/doc/html/rfc1035#section-4.1
+fn encode_dns_query(domain: &str, record_type: u16) -> anyhow::Result<Vec<u8>> {
+    // ID is 0 as recommended by RFC 8484, RD flag is set, 1 question
+    let mut query = vec![0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+
+    for label in domain.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            anyhow::bail!("Invalid domain name: {domain}");
+        }
+
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+
+    query.push(0);
+
+    query.extend_from_slice(&record_type.to_be_bytes());
+
+    // IN class
+    query.extend_from_slice(&[0, 1]);
+
+    Ok(query)
+}
+
+/// Skip domain name in the DNS message and return position after it
+fn skip_dns_name(message: &[u8], mut pos: usize) -> anyhow::Result<usize> {
+    loop {
+        let Some(len) = message.get(pos) else {
+            anyhow::bail!("Unexpected end of DNS message");
+        };
+
+        match len {
+            0 => return Ok(pos + 1),
+
+            // Compression pointer
+            len if len & 0b1100_0000 == 0b1100_0000 => return Ok(pos + 2),
+
+            len => pos += *len as usize + 1
+        }
+    }
+}
+
+/// Get A and AAAA records from the answer section of the DNS message
+fn parse_dns_answers(message: &[u8]) -> anyhow::Result<Vec<IpAddr>> {
+    if message.len() < 12 {
+        anyhow::bail!("DNS message is too short");
+    }
+
+    let questions = u16::from_be_bytes([message[4], message[5]]);
+    let answers = u16::from_be_bytes([message[6], message[7]]);
+
+    let mut pos = 12;
+
+    for _ in 0..questions {
+        // Name, type and class
+        pos = skip_dns_name(message, pos)? + 4;
+    }
+
+    let mut ips = Vec::new();
+
+    for _ in 0..answers {
+        pos = skip_dns_name(message, pos)?;
+
+        // Type, class, TTL and data length
+        let Some(header) = message.get(pos..pos + 10) else {
+            anyhow::bail!("Unexpected end of DNS message");
+        };
+
+        let record_type = u16::from_be_bytes([header[0], header[1]]);
+        let length = u16::from_be_bytes([header[8], header[9]]) as usize;
+
+        pos += 10;
+
+        let Some(data) = message.get(pos..pos + length) else {
+            anyhow::bail!("Unexpected end of DNS message");
+        };
+
+        match (record_type, length) {
+            (1, 4) => ips.push(IpAddr::V4(Ipv4Addr::new(data[0], data[1], data[2], data[3]))),
+
+            (28, 16) => {
+                let mut octets = [0; 16];
+
+                octets.copy_from_slice(data);
+
+                ips.push(IpAddr::V6(Ipv6Addr::from(octets)));
+            }
+
+            // CNAME and other records
+            _ => ()
+        }
+
+        pos += length;
+    }
+
+    Ok(ips)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dns_answers() -> anyhow::Result<()> {
+        let mut message = encode_dns_query("example.com", 1)?;
+
+        // QR flag and 2 answers
+        message[2] |= 0b1000_0000;
+        message[7] = 2;
+
+        // CNAME record pointing to the question's name
+        message.extend_from_slice(&[0xc0, 12, 0, 5, 0, 1, 0, 0, 0, 60, 0, 2, 0xc0, 12]);
+
+        // A record
+        message.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 93, 184, 215, 14]);
+
+        assert_eq!(parse_dns_answers(&message)?, vec![IpAddr::V4(Ipv4Addr::new(93, 184, 215, 14))]);
+
+        // Truncated message
+        assert!(parse_dns_answers(&message[..message.len() - 2]).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::marker::PhantomData;
+
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedResponse<T> {
+    /// Value of the `etag` header of the cached response
+    pub etag: Option<String>,
+
+    /// Value of the `last-modified` header of the cached response
+    pub last_modified: Option<String>,
+
+    pub value: T
+}
+
+/// JSON API responses cache stored on the disk between process restarts
+///
+/// If the cache file is younger than `ttl` the network request is skipped entirely.
+/// Otherwise a conditional GET request is sent using stored `etag` and `last-modified` headers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskCache<T> {
+    path: PathBuf,
+    ttl: Duration,
+
+    _value: PhantomData<T>
+}
+
+impl<T: Serialize + DeserializeOwned> DiskCache<T> {
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            path: path.into(),
+            ttl,
+
+            _value: PhantomData
+        }
+    }
+
+    #[inline]
+    /// Get path to the cache file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check if the cache file exists and is younger than the TTL
+    pub fn is_fresh(&self) -> bool {
+        self.path.metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .map(|elapsed| elapsed < self.ttl)
+            .unwrap_or(false)
+    }
+
+    /// Read cached response. Return `None` if the cache doesn't exist or can't be parsed
+    pub fn read(&self) -> Option<CachedResponse<T>> {
+        let cache = std::fs::read(&self.path).ok()?;
+
+        serde_json::from_slice(&cache).ok()
+    }
+
+    /// Write response to the cache file
+    pub fn write(&self, response: &CachedResponse<T>) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(&self.path, serde_json::to_vec(response)?)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    /// Get JSON response from the cache or request it from the given uri
+    ///
+    /// Timeout is amount of seconds
+    pub fn get(&self, uri: &str, timeout: u64) -> anyhow::Result<T> {
+        self.get_with(uri, timeout, |response| Ok(response.json::<T>()?))
+    }
+
+    /// Get response from the cache or request it from the given uri
+    /// and parse it using the given function
+    ///
+    /// Timeout is amount of seconds
+    #[tracing::instrument(level = "trace", skip(self, parse), fields(path = ?self.path))]
+    pub fn get_with(&self, uri: &str, timeout: u64, parse: impl FnOnce(&minreq::Response) -> anyhow::Result<T>) -> anyhow::Result<T> {
+        let cached = match self.read() {
+            Some(cached) if self.is_fresh() => {
+                tracing::trace!("Using cached response");
+
+                return Ok(cached.value);
+            }
+
+            cached => cached
+        };
+
+        let mut request = crate::request(minreq::get(uri))
+            .with_timeout(timeout);
+
+        if let Some(cached) = &cached {
+            if let Some(etag) = &cached.etag {
+                request = request.with_header("if-none-match", etag);
+            }
+
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.with_header("if-modified-since", last_modified);
+            }
+        }
+
+        let response = request.send()?;
+
+        // HTTP 304 = cached response is still actual
+        //
+        // https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/304
+        if response.status_code == 304 {
+            if let Some(cached) = cached {
+                tracing::trace!("Cached response is not modified");
+
+                // Rewrite the file to update its modification time
+                self.write(&cached)?;
+
+                return Ok(cached.value);
+            }
+        }
+
+        let cached = CachedResponse {
+            etag: response.headers.get("etag").cloned(),
+            last_modified: response.headers.get("last-modified").cloned(),
+            value: parse(&response)?
+        };
+
+        self.write(&cached)?;
+
+        Ok(cached.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disk_cache() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(".anime-game-core-test-disk-cache.json");
+
+        let cache = DiskCache::<Vec<u32>>::new(&path, Duration::from_secs(60));
+
+        cache.write(&CachedResponse {
+            etag: Some(String::from("\"example\"")),
+            last_modified: None,
+            value: vec![1, 2, 3]
+        })?;
+
+        assert!(cache.is_fresh());
+        assert_eq!(cache.read().map(|cached| cached.value), Some(vec![1, 2, 3]));
+
+        // Fresh cache doesn't send any requests
+        assert_eq!(cache.get("https://example.com", 1)?, vec![1, 2, 3]);
+
+        let cache = DiskCache::<Vec<u32>>::new(&path, Duration::ZERO);
+
+        assert!(!cache.is_fresh());
+
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,11 @@
 pub mod schema;
 
+use std::time::Duration;
+
+use crate::disk_cache::DiskCache;
+
+pub use crate::api::ApiError;
+
 use crate::genshin::consts::GameEdition;
 
 #[cached::proc_macro::cached(
@@ -9,13 +15,77 @@ use crate::genshin::consts::GameEdition;
 )]
 #[tracing::instrument(level = "trace")]
 pub fn request(game_edition: GameEdition) -> anyhow::Result<schema::GamePackage> {
+    request_with_timeout(game_edition, None)
+}
+
+#[tracing::instrument(level = "trace")]
+/// Fetch API using specified timeout instead of `REQUESTS_TIMEOUT`
+///
+/// Unlike `request` the response is not cached
+pub fn request_with_timeout(game_edition: GameEdition, timeout: Option<Duration>) -> anyhow::Result<schema::GamePackage> {
     tracing::trace!("Fetching API for {:?}", game_edition);
 
-    let schema: schema::Response = minreq::get(game_edition.api_uri())
-        .with_timeout(*crate::REQUESTS_TIMEOUT)
-        .send()?.json()?;
+    crate::api::request::<schema::Response>(game_edition.api_uri(), timeout, |game| game.game.biz.starts_with("hk4e_"))
+}
+
+#[tracing::instrument(level = "trace", skip(cache))]
+/// Fetch API using the disk cache
+///
+/// Timeout falls back to `REQUESTS_TIMEOUT` if not specified
+pub fn request_with_disk_cache(game_edition: GameEdition, cache: &DiskCache<schema::Response>, timeout: Option<Duration>) -> anyhow::Result<schema::GamePackage> {
+    tracing::trace!("Fetching API for {:?}", game_edition);
+
+    crate::api::request_with_disk_cache(game_edition.api_uri(), cache, timeout, |game| game.game.biz.starts_with("hk4e_"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::testing::MockApi;
+
+    const RESPONSE: &str = r#"{
+        "retcode": 0,
+        "message": "OK",
+        "data": {
+            "game_packages": [{
+                "game": { "id": "gopR6Cufr3", "biz": "hk4e_global" },
+                "main": {
+                    "major": {
+                        "version": "5.0.0",
+                        "game_pkgs": [],
+                        "audio_pkgs": [],
+                        "res_list_url": ""
+                    },
+                    "patches": []
+                },
+                "pre_download": {
+                    "major": null,
+                    "patches": []
+                }
+            }]
+        }
+    }"#;
+
+    #[test]
+    fn test_mock_request() -> anyhow::Result<()> {
+        MockApi::register(GameEdition::Global.api_uri(), RESPONSE);
+
+        let package = request_with_timeout(GameEdition::Global, None)?;
+
+        assert_eq!(package.main.major.version, "5.0.0");
+
+        MockApi::register(GameEdition::Global.api_uri(), r#"{ "retcode": -1, "message": "Service unavailable", "data": null }"#);
+
+        let err = request_with_timeout(GameEdition::Global, None).unwrap_err();
+
+        assert_eq!(err.downcast::<ApiError>()?, ApiError::ServiceUnavailable {
+            retcode: -1,
+            message: String::from("Service unavailable")
+        });
+
+        MockApi::unregister(GameEdition::Global.api_uri());
 
-    schema.data.game_packages.into_iter()
-        .find(|game| game.game.biz.starts_with("hk4e_"))
-        .ok_or_else(|| anyhow::anyhow!("Failed to find the game in the API"))
+        Ok(())
+    }
 }
@@ -1,15 +1,49 @@
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, Deserializer};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Response {
-    pub retcode: u16,
+    pub retcode: i32,
     pub message: String,
-    pub data: Data
+
+    /// Can be `null` if the API returned an error
+    pub data: Option<Data>
+}
+
+impl Response {
+    #[inline]
+    /// Get game SDK bundle info if the API provides it
+    pub fn sdk_package(&self) -> Option<&SdkPackage> {
+        self.data.as_ref()?.sdk_pkg.as_ref()
+    }
+}
+
+impl crate::api::GamesResponse for Response {
+    type Package = GamePackage;
+
+    #[inline]
+    fn retcode(&self) -> i32 {
+        self.retcode
+    }
+
+    #[inline]
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    #[inline]
+    fn into_packages(self) -> Option<Vec<GamePackage>> {
+        self.data.map(|data| data.game_packages)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Data {
-    pub game_packages: Vec<GamePackage>
+    pub game_packages: Vec<GamePackage>,
+
+    /// Game SDK bundle which can be updated separately from the game.
+    /// Not every edition provides it
+    #[serde(default)]
+    pub sdk_pkg: Option<SdkPackage>
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -68,3 +102,112 @@ pub struct GamePredownloadInfo {
     pub major: Option<GameLatestInfo>,
     pub patches: Vec<GamePatch>
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SdkPackage {
+    pub version: String,
+    pub url: String,
+    pub md5: String,
+
+    /// Archive size in bytes
+    #[serde(deserialize_with = "deserialize_size")]
+    pub size: u64
+}
+
+/// API sends sizes as strings, but accept numbers as well
+fn deserialize_size<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Size {
+        Number(u64),
+        String(String)
+    }
+
+    match Size::deserialize(deserializer)? {
+        Size::Number(size) => Ok(size),
+        Size::String(size) => size.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trimmed API response with fields unknown to the schema
+    // added on every level
+    const FORWARD_COMPAT_RESPONSE: &str = r#"{
+        "retcode": 0,
+        "message": "OK",
+        "new_root_field": true,
+        "data": {
+            "new_data_field": [1, 2, 3],
+            "game_packages": [{
+                "game": { "id": "gopR6Cufr3", "biz": "hk4e_global", "new_game_field": "value" },
+                "main": {
+                    "major": {
+                        "version": "5.0.0",
+                        "game_pkgs": [{
+                            "url": "https://example.com/game.zip.001",
+                            "md5": "d41d8cd98f00b204e9800998ecf8427e",
+                            "size": "100",
+                            "decompressed_size": "200",
+                            "new_segment_field": null
+                        }],
+                        "audio_pkgs": [{
+                            "language": "en-us",
+                            "url": "https://example.com/audio_en-us.zip",
+                            "md5": "d41d8cd98f00b204e9800998ecf8427e",
+                            "size": "10",
+                            "decompressed_size": "20",
+                            "new_audio_field": {}
+                        }],
+                        "res_list_url": "https://example.com/res",
+                        "new_major_field": 1
+                    },
+                    "patches": []
+                },
+                "pre_download": {
+                    "major": null,
+                    "patches": [],
+                    "new_predownload_field": "value"
+                }
+            }]
+        }
+    }"#;
+
+    #[test]
+    fn test_forward_compat() -> Result<(), serde_json::Error> {
+        let response = serde_json::from_str::<Response>(FORWARD_COMPAT_RESPONSE)?;
+
+        assert_eq!(response.sdk_package(), None);
+
+        let package = &response.data.unwrap().game_packages[0];
+
+        assert_eq!(package.game.biz, "hk4e_global");
+        assert_eq!(package.main.major.version, "5.0.0");
+        assert_eq!(package.main.major.audio_pkgs[0].language, "en-us");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sdk_package() -> Result<(), serde_json::Error> {
+        let response = serde_json::from_str::<Response>(r#"{
+            "retcode": 0,
+            "message": "OK",
+            "data": {
+                "game_packages": [],
+                "sdk_pkg": {
+                    "version": "1.0.0",
+                    "url": "https://example.com/sdk.zip",
+                    "md5": "d41d8cd98f00b204e9800998ecf8427e",
+                    "size": "1024"
+                }
+            }
+        }"#)?;
+
+        assert_eq!(response.sdk_package().map(|sdk| sdk.size), Some(1024));
+
+        Ok(())
+    }
+}
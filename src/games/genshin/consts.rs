@@ -39,6 +39,14 @@ impl GameEdition {
         }
     }
 
+    #[inline]
+    pub fn executable_name(&self) -> &str {
+        match self {
+            GameEdition::Global => concat!("Ge", "nsh", "inIm", "pact.exe"),
+            GameEdition::China  => concat!("Yu", "anS", "hen", ".exe")
+        }
+    }
+
     #[inline]
     pub fn telemetry_servers(&self) -> &[&str] {
         match self {
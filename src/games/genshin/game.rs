@@ -39,6 +39,11 @@ impl GameExt for Game {
         self.edition
     }
 
+    #[inline]
+    fn executable_name(&self) -> Option<&str> {
+        Some(self.edition.executable_name())
+    }
+
     #[tracing::instrument(level = "trace", ret)]
     /// Try to get latest game version
     fn get_latest_version(edition: GameEdition) -> anyhow::Result<Version> {
@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+use crate::installer::installer::{Installer, Update as InstallerUpdate};
+use crate::hash::HashAlgorithm;
+
+use super::api::schema::SdkPackage;
+
+#[tracing::instrument(level = "debug", skip(updater))]
+/// Download game SDK bundle and unpack it to the game folder
+///
+/// Archive size and md5 hash are verified against the API values.
+/// Installation errors are reported through the `updater` and returned as with `Installer::install`
+pub fn install_sdk(package: &SdkPackage, game_path: impl Into<PathBuf> + std::fmt::Debug, updater: impl Fn(InstallerUpdate) + Clone + Send + 'static) -> anyhow::Result<()> {
+    tracing::debug!("Installing game SDK {}", package.version);
+
+    let mut installer = Installer::new(&package.url)?;
+
+    if let Some(length) = installer.downloader.length() {
+        if length != package.size {
+            anyhow::bail!("SDK archive size mismatch. Expected: {}. Got: {length}", package.size);
+        }
+    }
+
+    // Verify downloaded archive before unpacking it
+    installer.downloader = installer.downloader.with_expected_hash(&package.md5, HashAlgorithm::Md5);
+
+    installer.install(game_path, updater)?;
+
+    Ok(())
+}
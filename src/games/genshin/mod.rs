@@ -8,6 +8,12 @@ pub mod telemetry;
 #[cfg(feature = "install")]
 pub mod repairer;
 
+#[cfg(feature = "install")]
+pub mod installer;
+
+#[cfg(feature = "wine")]
+pub mod wine_setup;
+
 pub mod prelude {
     pub use super::consts::*;
     pub use super::version_diff::*;
@@ -17,4 +23,10 @@ pub mod prelude {
 
     #[cfg(feature = "install")]
     pub use super::repairer;
+
+    #[cfg(feature = "install")]
+    pub use super::installer;
+
+    #[cfg(feature = "wine")]
+    pub use super::wine_setup;
 }
@@ -11,24 +11,35 @@ use crate::repairer::IntegrityFile;
 fn try_get_some_integrity_files<T: AsRef<str>>(game_edition: GameEdition, file_name: T, timeout: Option<u64>) -> anyhow::Result<Vec<IntegrityFile>> {
     let decompressed_path = api::request(game_edition)?.main.major.res_list_url;
 
-    let pkg_version = minreq::get(format!("{decompressed_path}/{}", file_name.as_ref()))
+    let pkg_version = crate::request(minreq::get(format!("{decompressed_path}/{}", file_name.as_ref())))
         .with_timeout(timeout.unwrap_or(*crate::REQUESTS_TIMEOUT))
         .send()?;
 
+    Ok(parse_pkg_version(&String::from_utf8_lossy(pkg_version.as_bytes()), &decompressed_path))
+}
+
+/// Parse `pkg_version` file content
+///
+/// Lines which can't be parsed are skipped
+pub(crate) fn parse_pkg_version(pkg_version: &str, base_url: &str) -> Vec<IntegrityFile> {
     let mut files = Vec::new();
 
-    for line in String::from_utf8_lossy(pkg_version.as_bytes()).lines() {
+    for line in pkg_version.lines() {
         if let Ok(value) = serde_json::from_str::<serde_json::Value>(line) {
+            let (Some(path), Some(md5), Some(size)) = (value["remoteName"].as_str(), value["md5"].as_str(), value["fileSize"].as_u64()) else {
+                continue;
+            };
+
             files.push(IntegrityFile {
-                path: PathBuf::from(value["remoteName"].as_str().unwrap()),
-                md5: value["md5"].as_str().unwrap().to_string(),
-                size: value["fileSize"].as_u64().unwrap(),
-                base_url: decompressed_path.clone()
+                path: PathBuf::from(path),
+                md5: md5.to_string(),
+                size,
+                base_url: base_url.to_string()
             });
         }
     }
 
-    Ok(files)
+    files
 }
 
 /// Try to list latest game files
@@ -106,3 +117,24 @@ pub fn try_get_unused_voice_files(game_edition: GameEdition, game_dir: impl Into
 
     crate::repairer::try_get_unused_files(game_dir, used_files, [])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pkg_version() {
+        let files = parse_pkg_version(concat!(
+            "{\"remoteName\": \"UnityPlayer.dll\", \"md5\": \"8c8c3d845b957e4cb84c662bed44d072\", \"fileSize\": 33466104}\n",
+            "{\"remoteName\": \"broken.dll\"}\n",
+            "not a json"
+        ), "https://example.com");
+
+        assert_eq!(files, vec![IntegrityFile {
+            path: PathBuf::from("UnityPlayer.dll"),
+            md5: String::from("8c8c3d845b957e4cb84c662bed44d072"),
+            size: 33466104,
+            base_url: String::from("https://example.com")
+        }]);
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use super::consts::GameEdition;
 
 /// Check whether telemetry servers disabled
@@ -16,11 +18,33 @@ use super::consts::GameEdition;
 pub fn is_disabled(game_edition: GameEdition) -> anyhow::Result<Option<String>> {
     tracing::debug!("Checking telemetry servers status");
 
-    for server in game_edition.telemetry_servers() {
-        if crate::check_domain::available(server)? {
+    for (server, available) in crate::check_domain::available_bulk(game_edition.telemetry_servers()) {
+        if available? {
+            tracing::warn!("Server is not disabled: {server}");
+
+            return Ok(Some(server));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Check whether telemetry servers disabled, including the ones found in the patch scripts
+///
+/// If some of them is not disabled, then this function will return its address
+#[tracing::instrument(level = "debug", skip(patch_folder))]
+pub fn is_disabled_with_patch(game_edition: GameEdition, patch_folder: impl AsRef<Path>) -> anyhow::Result<Option<String>> {
+    tracing::debug!("Checking telemetry servers status");
+
+    let discovered = crate::telemetry::discover_from_patch(patch_folder)?;
+
+    let servers = crate::telemetry::merge_servers(game_edition.telemetry_servers(), discovered);
+
+    for (server, available) in crate::check_domain::available_bulk(&servers) {
+        if available? {
             tracing::warn!("Server is not disabled: {server}");
 
-            return Ok(Some(server.to_string()));
+            return Ok(Some(server));
         }
     }
 
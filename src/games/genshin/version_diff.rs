@@ -14,6 +14,7 @@ use crate::{
     installer::{
         downloader::{Downloader, DownloadingError},
         installer::Update as InstallerUpdate,
+        lock::{LockFile, LockError},
         free_space,
         archives::Archive
     },
@@ -61,6 +62,10 @@ pub enum DiffDownloadingError {
     #[error("{0}")]
     DownloadingError(#[from] DownloadingError),
 
+    /// Installation folder is locked by another process
+    #[error("{0}")]
+    LockError(#[from] LockError),
+
     /// Failed to apply hdiff patch
     #[error("Failed to apply hdiff patch: {0}")]
     HdiffPatch(String),
@@ -71,7 +76,13 @@ pub enum DiffDownloadingError {
     /// your game installation path and thus indicates that it doesn't know
     /// where this package needs to be installed
     #[error("Path to the component's downloading folder is not specified")]
-    PathNotSpecified
+    PathNotSpecified,
+
+    /// Downloading uri is empty or doesn't use https
+    ///
+    /// `(uri)`
+    #[error("Invalid downloading uri: {0:?}")]
+    InvalidDownloadUri(String)
 }
 
 impl From<minreq::Error> for DiffDownloadingError {
@@ -164,6 +175,34 @@ pub enum VersionDiff {
 }
 
 impl VersionDiff {
+    /// Check that all the downloading uris are non-empty https links
+    ///
+    /// API can return empty values if something went wrong on its side
+    pub fn validate(&self) -> Result<(), DiffDownloadingError> {
+        let uris = match self {
+            // Nothing to download
+            Self::Latest { .. } |
+            Self::Outdated { .. } => return Ok(()),
+
+            Self::Predownload { uri, .. } |
+            Self::Diff { uri, .. } => std::slice::from_ref(uri),
+
+            Self::NotInstalled { segments_uris, .. } => segments_uris.as_slice()
+        };
+
+        if uris.is_empty() {
+            return Err(DiffDownloadingError::InvalidDownloadUri(String::new()));
+        }
+
+        for uri in uris {
+            if !uri.starts_with("https://") || uri.len() == "https://".len() {
+                return Err(DiffDownloadingError::InvalidDownloadUri(uri.to_owned()));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Get `.version` file path
     pub fn version_file_path(&self) -> Option<PathBuf> {
         match self {
@@ -318,12 +357,35 @@ impl VersionDiffExt for VersionDiff {
         }
     }
 
+    fn download_to(&mut self, folder: impl AsRef<Path>, progress: impl Fn(u64, u64) + Send + 'static) -> Result<(), Self::Error> {
+        // Latest version doesn't have file name
+        // so we can't pass it to the `download_as` method
+        if let Self::Latest { .. } = self {
+            tracing::debug!("Version is already latest");
+
+            return Ok(());
+        }
+
+        let filename = self.file_name()
+            .expect("Failed to resolve downloading file name");
+
+        self.download_as(folder.as_ref().join(filename), progress)
+    }
+
     fn download_as(&mut self, path: impl AsRef<Path>, progress: impl Fn(u64, u64) + Send + 'static) -> Result<(), Self::Error> {
         tracing::debug!("Downloading version difference");
 
+        self.validate()?;
+
         let mut downloader = Downloader::new(match self {
+            // Nothing to download
+            Self::Latest { .. } => {
+                tracing::debug!("Version is already latest");
+
+                return Ok(());
+            }
+
             // Can't be downloaded
-            Self::Latest { .. } => return Err(Self::Error::AlreadyLatest),
             Self::Outdated { .. } => return Err(Self::Error::Outdated),
 
             // Can be downloaded
@@ -343,13 +405,50 @@ impl VersionDiffExt for VersionDiff {
         Ok(())
     }
 
+    fn install(&self, updater: impl Fn(Self::Update) + Clone + Send + 'static) -> Result<(), Self::Error> {
+        // Latest version doesn't have installation path
+        // so we can't pass it to the `install_to` method
+        if let Self::Latest { version, .. } = self {
+            (updater)(DiffUpdate::InstallerUpdate(InstallerUpdate::AlreadyLatest(*version)));
+
+            return Ok(());
+        }
+
+        let path = self.installation_path()
+            .expect("Difference installation path is not provided");
+
+        self.install_to(path, updater)
+    }
+
     fn install_to(&self, path: impl AsRef<Path>, updater: impl Fn(Self::Update) + Clone + Send + 'static) -> Result<(), Self::Error> {
+        self.install_to_locked(None, path, updater)
+    }
+}
+
+impl VersionDiff {
+    /// Same as `install_to`, but uses already held lock of the installation folder
+    ///
+    /// Locks are exclusive within the process, so this is needed to install
+    /// multiple differences into the same folder at the same time
+    pub fn install_to_with_lock(&self, lock: &LockFile, path: impl AsRef<Path>, updater: impl Fn(DiffUpdate) + Clone + Send + 'static) -> Result<(), DiffDownloadingError> {
+        self.install_to_locked(Some(lock), path, updater)
+    }
+
+    fn install_to_locked(&self, lock: Option<&LockFile>, path: impl AsRef<Path>, updater: impl Fn(DiffUpdate) + Clone + Send + 'static) -> Result<(), DiffDownloadingError> {
         tracing::debug!("Installing version difference");
 
         let uris = match self {
+            // Nothing to install
+            Self::Latest { version, .. } => {
+                tracing::debug!("Version is already latest");
+
+                (updater)(DiffUpdate::InstallerUpdate(InstallerUpdate::AlreadyLatest(*version)));
+
+                return Ok(());
+            }
+
             // Can't be installed
-            Self::Latest { .. } => return Err(Self::Error::AlreadyLatest),
-            Self::Outdated { .. } => return Err(Self::Error::Outdated),
+            Self::Outdated { .. } => return Err(DiffDownloadingError::Outdated),
 
             // Can be installed
             Self::Predownload { uri, .. } |
@@ -358,7 +457,16 @@ impl VersionDiffExt for VersionDiff {
             Self::NotInstalled { segments_uris, .. } => segments_uris.to_owned()
         };
 
+        self.validate()?;
+
         let path = path.as_ref().to_path_buf();
+
+        // Prevent other processes from installing anything into the same folder.
+        // The lock is released when this variable is dropped
+        let _lock = match lock {
+            Some(lock) if lock.locks(&path) => None,
+            _ => Some(LockFile::new(&path)?)
+        };
         let temp_folder = self.temp_folder();
 
         let downloaded_size = self.downloaded_size().expect("Failed to retrieve downloaded size");
@@ -566,7 +674,7 @@ impl VersionDiffExt for VersionDiff {
         if let Ok(files) = std::fs::read_to_string(path.join("hdifffiles.txt")) {
             tracing::debug!("Applying hdiff patches");
 
-            (updater)(Self::Update::ApplyingHdiffStarted);
+            (updater)(DiffUpdate::ApplyingHdiffStarted);
 
             let files = files.lines().collect::<Vec<&str>>();
             let hdiffs = files.len() as u64;
@@ -600,13 +708,13 @@ impl VersionDiffExt for VersionDiff {
                         Ok(None) => {
                             tracing::error!("Failed to repair corrupted file: not found");
 
-                            return Err(Self::Error::HdiffPatch(err.to_string()))
+                            return Err(DiffDownloadingError::HdiffPatch(err.to_string()))
                         }
 
                         Err(repair_fail) => {
                             tracing::error!("Failed to repair corrupted file: {repair_fail}");
 
-                            return Err(Self::Error::HdiffPatch(err.to_string()))
+                            return Err(DiffDownloadingError::HdiffPatch(err.to_string()))
                         }
                     }
 
@@ -628,13 +736,13 @@ impl VersionDiffExt for VersionDiff {
                         .expect(&format!("Failed to rename hdiff patch: {:?}", file));
                 }
 
-                (updater)(Self::Update::ApplyingHdiffProgress(i as u64 + 1, hdiffs));
+                (updater)(DiffUpdate::ApplyingHdiffProgress(i as u64 + 1, hdiffs));
             }
 
             std::fs::remove_file(path.join("hdifffiles.txt"))
                 .expect("Failed to remove hdifffiles.txt");
 
-            (updater)(Self::Update::ApplyingHdiffFinished);
+            (updater)(DiffUpdate::ApplyingHdiffFinished);
         }
 
         tracing::debug!("Deleting outdated files");
@@ -645,7 +753,7 @@ impl VersionDiffExt for VersionDiff {
             let files = files.lines().collect::<Vec<&str>>();
             let files_len = files.len() as u64;
 
-            (updater)(Self::Update::RemovingOutdatedStarted);
+            (updater)(DiffUpdate::RemovingOutdatedStarted);
 
             // AnimeGame_Data/Plugins/metakeeper.dll
             for (i, file) in files.into_iter().enumerate() {
@@ -654,15 +762,58 @@ impl VersionDiffExt for VersionDiff {
                 std::fs::remove_file(&file)
                     .expect(&format!("Failed to remove outdated file: {:?}", file));
 
-                (updater)(Self::Update::RemovingOutdatedProgress(i as u64 + 1, files_len));
+                (updater)(DiffUpdate::RemovingOutdatedProgress(i as u64 + 1, files_len));
             }
 
             std::fs::remove_file(path.join("deletefiles.txt"))
                 .expect("Failed to remove deletefiles.txt");
 
-            (updater)(Self::Update::RemovingOutdatedFinished);
+            (updater)(DiffUpdate::RemovingOutdatedFinished);
         }
 
         Ok(())
     }
 }
+
+#[cfg(all(test, feature = "install"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn test_latest() -> Result<(), DiffDownloadingError> {
+        let version = Version::new(5, 0, 0);
+
+        let mut diff = VersionDiff::Latest {
+            version,
+            edition: GameEdition::Global
+        };
+
+        let updates = Arc::new(Mutex::new(Vec::new()));
+
+        let updater = {
+            let updates = updates.clone();
+
+            move |update| updates.lock().unwrap().push(update)
+        };
+
+        let folder = std::env::temp_dir().join(".anime-game-core-test-genshin-latest-diff");
+
+        diff.install(updater.clone())?;
+        diff.install_to(&folder, updater)?;
+
+        diff.download_to(&folder, |_, _| {})?;
+        diff.download_as(folder.join("game.zip"), |_, _| {})?;
+
+        // Nothing is installed or downloaded
+        assert!(!folder.exists());
+
+        assert_eq!(*updates.lock().unwrap(), vec![
+            DiffUpdate::InstallerUpdate(InstallerUpdate::AlreadyLatest(version)),
+            DiffUpdate::InstallerUpdate(InstallerUpdate::AlreadyLatest(version))
+        ]);
+
+        Ok(())
+    }
+}
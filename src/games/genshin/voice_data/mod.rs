@@ -1,6 +1,9 @@
 pub mod locale;
 pub mod package;
 
+#[cfg(feature = "install")]
+pub use package::{verify, disk_usage, all_languages_disk_usage, uninstall};
+
 pub mod prelude {
     pub use super::locale::VoiceLocale;
     pub use super::package::VoicePackage;
@@ -15,6 +15,18 @@ use crate::genshin::voice_data::locale::VoiceLocale;
 #[cfg(feature = "install")]
 use crate::genshin::version_diff::*;
 
+#[cfg(feature = "install")]
+use crate::traits::version_diff::VersionDiffExt;
+
+#[cfg(feature = "install")]
+use crate::installer::installer::Update as InstallerUpdate;
+
+#[cfg(feature = "install")]
+use crate::installer::lock::LockFile;
+
+#[cfg(feature = "install")]
+use serde::{Serialize, Deserialize};
+
 /// List of voiceover sizes
 ///
 /// Format: `(version, english, japanese, korean, chinese)`
@@ -192,6 +204,16 @@ impl VoicePackage {
         }
     }
 
+    /// Get voice package archive size in bytes from the game API
+    ///
+    /// Return `None` if the package is installed or its size is unknown
+    pub fn download_size(&self) -> Option<u64> {
+        match self {
+            VoicePackage::Installed { .. } => None,
+            VoicePackage::NotInstalled { data, .. } => data.size.parse::<u64>().ok()
+        }
+    }
+
     #[inline]
     /// This method will return `true` if the package has `VoicePackage::Installed` enum value
     ///
@@ -483,3 +505,292 @@ impl VoicePackage {
         }
     }
 }
+
+#[cfg(feature = "install")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParallelInstallUpdate {
+    /// Update of the package installation
+    ///
+    /// `(package locale, update)`
+    Package(VoiceLocale, DiffUpdate),
+
+    /// Aggregated downloading progress of all the packages
+    ///
+    /// `(current bytes, total bytes)`
+    DownloadingProgress(u64, u64)
+}
+
+#[cfg(feature = "install")]
+/// Download and install voice packages concurrently using given amount of threads
+///
+/// Every package update is reported with its locale, and downloading progress
+/// is additionally reported as an aggregated value for all the packages
+#[tracing::instrument(level = "debug", skip(updater))]
+pub fn install_all_parallel(
+    voices: &[VoicePackage],
+    game_path: impl AsRef<Path> + std::fmt::Debug,
+    threads: usize,
+    updater: impl Fn(ParallelInstallUpdate) + Send + Sync + 'static
+) -> anyhow::Result<()> {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+    let diffs = voices.iter()
+        .map(|voice| Ok((voice.locale(), voice.try_get_diff()?)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let total = diffs.iter()
+        .map(|(_, diff)| diff.downloaded_size().unwrap_or(0))
+        .sum::<u64>();
+
+    let progress = Arc::new(diffs.iter().map(|_| AtomicU64::new(0)).collect::<Vec<_>>());
+    let updater = Arc::new(updater);
+
+    let next = AtomicUsize::new(0);
+    let game_path = game_path.as_ref();
+
+    // Locks are exclusive so the folder is locked once for all the packages
+    let lock = LockFile::new(game_path)?;
+
+    std::thread::scope(|scope| {
+        let workers = (0..threads.clamp(1, diffs.len().max(1)))
+            .map(|_| scope.spawn(|| -> Result<(), DiffDownloadingError> {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+
+                    let Some((locale, diff)) = diffs.get(index) else {
+                        return Ok(());
+                    };
+
+                    let locale = *locale;
+                    let progress = progress.clone();
+                    let updater = updater.clone();
+
+                    tracing::debug!("Installing {} voice package", locale.to_code());
+
+                    diff.install_to_with_lock(&lock, game_path, move |update| {
+                        if let DiffUpdate::InstallerUpdate(InstallerUpdate::DownloadingProgress(current, _)) = &update {
+                            progress[index].store(*current, Ordering::Relaxed);
+
+                            let current = progress.iter()
+                                .map(|current| current.load(Ordering::Relaxed))
+                                .sum();
+
+                            (updater)(ParallelInstallUpdate::DownloadingProgress(current, total));
+                        }
+
+                        (updater)(ParallelInstallUpdate::Package(locale, update));
+                    })?;
+                }
+            }))
+            .collect::<Vec<_>>();
+
+        for worker in workers {
+            worker.join()
+                .map_err(|_| anyhow::anyhow!("Voice package installation thread panicked"))??;
+        }
+
+        Ok(())
+    })
+}
+
+#[cfg(feature = "install")]
+/// Get list of the voice package files
+///
+/// Files list is fetched from the game API. If it's not available,
+/// then the package's local `Audio_<locale>_pkg_version` manifest is used
+fn get_voice_files(game_edition: GameEdition, locale: VoiceLocale, game_path: &Path) -> anyhow::Result<Vec<crate::repairer::IntegrityFile>> {
+    match crate::genshin::repairer::try_get_voice_integrity_files(game_edition, locale, None) {
+        Ok(files) => Ok(files),
+
+        Err(err) => {
+            tracing::warn!("Failed to fetch {} voice package files list: {err}. Using local manifest", locale.to_code());
+
+            let manifest = std::fs::read(game_path.join(format!("Audio_{}_pkg_version", locale.to_folder())))?;
+
+            Ok(crate::genshin::repairer::parse_pkg_version(&String::from_utf8_lossy(&manifest), ""))
+        }
+    }
+}
+
+#[cfg(feature = "install")]
+/// Get list of the installed voice package files
+///
+/// Unlike `get_voice_files` the package's local `Audio_<locale>_pkg_version` manifest
+/// is preferred because it describes installed version of the package
+fn get_installed_voice_files(game_edition: GameEdition, locale: VoiceLocale, game_path: &Path) -> anyhow::Result<Vec<crate::repairer::IntegrityFile>> {
+    match std::fs::read(game_path.join(format!("Audio_{}_pkg_version", locale.to_folder()))) {
+        Ok(manifest) => Ok(crate::genshin::repairer::parse_pkg_version(&String::from_utf8_lossy(&manifest), "")),
+        Err(_) => Ok(crate::genshin::repairer::try_get_voice_integrity_files(game_edition, locale, None)?)
+    }
+}
+
+#[cfg(feature = "install")]
+/// Verify files of the installed voice package
+///
+/// Return list of files (relative to the game folder) which failed verification
+#[tracing::instrument(level = "debug")]
+pub fn verify(pack: &VoicePackage, game_path: impl AsRef<Path> + std::fmt::Debug) -> anyhow::Result<Vec<PathBuf>> {
+    let game_path = game_path.as_ref();
+
+    Ok(get_voice_files(pack.game_edition(), pack.locale(), game_path)?
+        .into_iter()
+        .filter(|file| !file.verify(game_path))
+        .map(|file| file.path)
+        .collect())
+}
+
+#[cfg(feature = "install")]
+/// Get size of the installed voice package files in bytes
+#[tracing::instrument(level = "debug")]
+pub fn disk_usage(pack: &VoicePackage, game_path: impl AsRef<Path> + std::fmt::Debug) -> anyhow::Result<u64> {
+    get_locale_disk_usage(pack.game_edition(), pack.locale(), game_path.as_ref())
+}
+
+#[cfg(feature = "install")]
+/// Get size of all the installed voice packages in bytes
+#[tracing::instrument(level = "debug")]
+pub fn all_languages_disk_usage(game_path: impl AsRef<Path> + std::fmt::Debug, game_edition: GameEdition) -> anyhow::Result<std::collections::HashMap<VoiceLocale, u64>> {
+    let game_path = game_path.as_ref();
+
+    let mut usage = std::collections::HashMap::new();
+
+    for locale in VoiceLocale::list() {
+        if get_voice_package_path(game_path, game_edition, *locale).exists() {
+            usage.insert(*locale, get_locale_disk_usage(game_edition, *locale, game_path)?);
+        }
+    }
+
+    Ok(usage)
+}
+
+#[cfg(feature = "install")]
+fn get_locale_disk_usage(game_edition: GameEdition, locale: VoiceLocale, game_path: &Path) -> anyhow::Result<u64> {
+    Ok(get_voice_files(game_edition, locale, game_path)?
+        .into_iter()
+        .filter_map(|file| game_path.join(file.path).metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum())
+}
+
+#[cfg(feature = "install")]
+/// Remove installed voice package files
+///
+/// Files which are also used by other installed voice packages are kept.
+/// Voice package folder is removed, and the language is excluded
+/// from the game's `Persistent/audio_lang_14` file
+#[tracing::instrument(level = "debug")]
+pub fn uninstall(pack: &VoicePackage, game_path: impl AsRef<Path> + std::fmt::Debug) -> anyhow::Result<()> {
+    let game_path = game_path.as_ref();
+    let game_edition = pack.game_edition();
+    let locale = pack.locale();
+
+    tracing::debug!("Uninstalling {} voice package", locale.to_code());
+
+    let mut used_files = std::collections::HashSet::new();
+
+    for other in VoiceLocale::list() {
+        if *other != locale && get_voice_package_path(game_path, game_edition, *other).exists() {
+            used_files.extend(get_installed_voice_files(game_edition, *other, game_path)?.into_iter().map(|file| file.path));
+        }
+    }
+
+    for file in get_installed_voice_files(game_edition, locale, game_path)? {
+        if used_files.contains(&file.path) {
+            continue;
+        }
+
+        let path = game_path.join(&file.path);
+
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
+
+    // Remove remaining files and the `.version` file
+    let package_path = get_voice_package_path(game_path, game_edition, locale);
+
+    if package_path.exists() {
+        std::fs::remove_dir_all(package_path)?;
+    }
+
+    let manifest = game_path.join(format!("Audio_{}_pkg_version", locale.to_folder()));
+
+    if manifest.exists() {
+        std::fs::remove_file(manifest)?;
+    }
+
+    // The game reads installed voice languages from this file
+    let audio_lang = game_path.join(game_edition.data_folder()).join("Persistent/audio_lang_14");
+
+    if audio_lang.exists() {
+        let languages = std::fs::read_to_string(&audio_lang)?;
+
+        let languages = languages.lines()
+            .filter(|language| language.trim() != locale.to_folder())
+            .map(|language| format!("{language}\n"))
+            .collect::<String>();
+
+        std::fs::write(audio_lang, languages)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "install"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uninstall() -> anyhow::Result<()> {
+        let game_path = std::env::temp_dir().join(".anime-game-core-test-voice-uninstall");
+        let game_edition = GameEdition::Global;
+        let locale = VoiceLocale::English;
+
+        let package_path = get_voice_package_path(&game_path, game_edition, locale);
+        let audio_lang = game_path.join(game_edition.data_folder()).join("Persistent/audio_lang_14");
+
+        std::fs::create_dir_all(&package_path)?;
+        std::fs::create_dir_all(audio_lang.parent().unwrap())?;
+
+        std::fs::write(package_path.join(".version"), [5, 0, 0])?;
+        std::fs::write(package_path.join("voice.pck"), "voice")?;
+        std::fs::write(&audio_lang, format!("{}\nJapanese\n", locale.to_folder()))?;
+
+        let relative_path = package_path.join("voice.pck")
+            .strip_prefix(&game_path)?
+            .to_string_lossy()
+            .to_string();
+
+        std::fs::write(
+            game_path.join(format!("Audio_{}_pkg_version", locale.to_folder())),
+            format!("{{\"remoteName\": \"{relative_path}\", \"md5\": \"\", \"fileSize\": 5}}\n")
+        )?;
+
+        let package = VoicePackage::new(&package_path, game_edition).unwrap();
+
+        uninstall(&package, &game_path)?;
+
+        let package = VoicePackage::NotInstalled {
+            locale,
+            version: Version::new(5, 0, 0),
+            data: AudioPackage {
+                language: String::from("en-us"),
+                url: String::new(),
+                md5: String::new(),
+                size: String::from("0"),
+                decompressed_size: String::from("0")
+            },
+            game_path: Some(game_path.clone()),
+            game_edition
+        };
+
+        assert!(!package.is_installed_in(&game_path));
+        assert!(VoicePackage::new(&package_path, game_edition).is_none());
+        assert_eq!(std::fs::read_to_string(&audio_lang)?, "Japanese\n");
+
+        std::fs::remove_dir_all(game_path)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::time::{Duration, Instant};
+
+use serde::{Serialize, Deserialize};
+
+use wincompatlib::wine::ext::*;
+
+/// Maximal time `wineboot` is allowed to spend on prefix creation
+pub const WINEBOOT_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WineSetupUpdate {
+    /// `(prefix path)`
+    Creating(PathBuf),
+
+    Done,
+
+    /// `(error message)`
+    Failed(String)
+}
+
+#[inline]
+/// Check if wine prefix is already initialized
+pub fn is_prefix_created(prefix: impl AsRef<Path>) -> bool {
+    prefix.as_ref().join("system.reg").exists()
+}
+
+#[tracing::instrument(level = "debug", skip(wine, progress))]
+/// Create wine prefix using `wineboot -i` if it doesn't exist
+pub fn ensure_prefix(wine: impl WineWithExt + WineRunExt, prefix: impl AsRef<Path> + std::fmt::Debug, progress: impl Fn(WineSetupUpdate)) -> anyhow::Result<()> {
+    ensure_prefix_with(prefix.as_ref(), progress, |prefix| {
+        let mut child = wine.with_prefix(prefix)
+            .run_args(["wineboot", "-i"])
+            .map_err(|err| anyhow::anyhow!("Failed to run wineboot: {err}"))?;
+
+        wait_with_timeout(&mut child, WINEBOOT_TIMEOUT)
+    })
+}
+
+/// Create wine prefix using the given function if it doesn't exist
+/// and report the progress
+fn ensure_prefix_with(prefix: &Path, progress: impl Fn(WineSetupUpdate), create: impl FnOnce(&Path) -> anyhow::Result<()>) -> anyhow::Result<()> {
+    if is_prefix_created(prefix) {
+        tracing::debug!("Wine prefix already exists");
+
+        (progress)(WineSetupUpdate::Done);
+
+        return Ok(());
+    }
+
+    tracing::debug!("Creating wine prefix");
+
+    (progress)(WineSetupUpdate::Creating(prefix.to_path_buf()));
+
+    if let Err(err) = create(prefix) {
+        tracing::error!("Failed to create wine prefix: {err}");
+
+        (progress)(WineSetupUpdate::Failed(err.to_string()));
+
+        return Err(err);
+    }
+
+    (progress)(WineSetupUpdate::Done);
+
+    Ok(())
+}
+
+/// Wait for the process to finish successfully
+///
+/// The process is killed if it doesn't finish in time
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> anyhow::Result<()> {
+    let started = Instant::now();
+
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => anyhow::bail!("wineboot exited with {status}"),
+
+            Ok(None) => {
+                if started.elapsed() > timeout {
+                    // Kill the process so it doesn't keep the prefix in a half-created state
+                    #[allow(unused_must_use)] {
+                        child.kill();
+                        child.wait();
+                    }
+
+                    anyhow::bail!("wineboot timed out after {} seconds", timeout.as_secs());
+                }
+
+                std::thread::sleep(Duration::from_millis(100));
+            }
+
+            Err(err) => anyhow::bail!("Failed to wait for wineboot: {err}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::process::Command;
+
+    use super::*;
+
+    #[test]
+    fn test_ensure_prefix() -> anyhow::Result<()> {
+        let prefix = std::env::temp_dir().join(".anime-game-core-test-wine-prefix");
+
+        if prefix.exists() {
+            std::fs::remove_dir_all(&prefix)?;
+        }
+
+        let updates = RefCell::new(Vec::new());
+
+        let result = ensure_prefix_with(&prefix, |update| updates.borrow_mut().push(update), |_| {
+            anyhow::bail!("wineboot exited with exit status: 1")
+        });
+
+        assert!(result.is_err());
+
+        assert_eq!(updates.take(), vec![
+            WineSetupUpdate::Creating(prefix.clone()),
+            WineSetupUpdate::Failed(String::from("wineboot exited with exit status: 1"))
+        ]);
+
+        ensure_prefix_with(&prefix, |update| updates.borrow_mut().push(update), |prefix| {
+            std::fs::create_dir_all(prefix)?;
+            std::fs::write(prefix.join("system.reg"), "")?;
+
+            Ok(())
+        })?;
+
+        assert!(is_prefix_created(&prefix));
+
+        assert_eq!(updates.take(), vec![
+            WineSetupUpdate::Creating(prefix.clone()),
+            WineSetupUpdate::Done
+        ]);
+
+        // Already created prefix is not touched
+        ensure_prefix_with(&prefix, |update| updates.borrow_mut().push(update), |_| {
+            panic!("Prefix is created twice")
+        })?;
+
+        assert_eq!(updates.take(), vec![WineSetupUpdate::Done]);
+
+        std::fs::remove_dir_all(prefix)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wait_with_timeout() -> anyhow::Result<()> {
+        assert!(wait_with_timeout(&mut Command::new("true").spawn()?, Duration::from_secs(5)).is_ok());
+        assert!(wait_with_timeout(&mut Command::new("false").spawn()?, Duration::from_secs(5)).is_err());
+
+        let mut child = Command::new("sleep").arg("10").spawn()?;
+
+        assert!(wait_with_timeout(&mut child, Duration::from_millis(200)).is_err());
+
+        // Timed out process is killed
+        assert!(child.try_wait()?.is_some());
+
+        Ok(())
+    }
+}
@@ -1,5 +1,11 @@
 pub mod schema;
 
+use std::time::Duration;
+
+use crate::disk_cache::DiskCache;
+
+pub use crate::api::ApiError;
+
 use crate::honkai::consts::GameEdition;
 
 #[cached::proc_macro::cached(
@@ -9,13 +15,25 @@ use crate::honkai::consts::GameEdition;
 )]
 #[tracing::instrument(level = "trace")]
 pub fn request(game_edition: GameEdition) -> anyhow::Result<schema::GamePackage> {
+    request_with_timeout(game_edition, None)
+}
+
+#[tracing::instrument(level = "trace")]
+/// Fetch API using specified timeout instead of `REQUESTS_TIMEOUT`
+///
+/// Unlike `request` the response is not cached
+pub fn request_with_timeout(game_edition: GameEdition, timeout: Option<Duration>) -> anyhow::Result<schema::GamePackage> {
     tracing::trace!("Fetching API for {:?}", game_edition);
 
-    let schema: schema::Response = minreq::get(game_edition.api_uri())
-        .with_timeout(*crate::REQUESTS_TIMEOUT)
-        .send()?.json()?;
+    crate::api::request::<schema::Response>(game_edition.api_uri(), timeout, |game| game.game.id == game_edition.api_game_id())
+}
+
+#[tracing::instrument(level = "trace", skip(cache))]
+/// Fetch API using the disk cache
+///
+/// Timeout falls back to `REQUESTS_TIMEOUT` if not specified
+pub fn request_with_disk_cache(game_edition: GameEdition, cache: &DiskCache<schema::Response>, timeout: Option<Duration>) -> anyhow::Result<schema::GamePackage> {
+    tracing::trace!("Fetching API for {:?}", game_edition);
 
-    schema.data.game_packages.into_iter()
-        .find(|game| game.game.id == game_edition.api_game_id())
-        .ok_or_else(|| anyhow::anyhow!("Failed to find the game in the API"))
+    crate::api::request_with_disk_cache(game_edition.api_uri(), cache, timeout, |game| game.game.id == game_edition.api_game_id())
 }
@@ -59,6 +59,11 @@ impl GameEdition {
         "BH3_Data"
     }
 
+    #[inline]
+    pub fn executable_name(&self) -> &str {
+        "BH3.exe"
+    }
+
     #[inline]
     pub fn telemetry_servers(&self) -> &[&str] {
         match self {
@@ -16,13 +16,29 @@ use super::consts::GameEdition;
 pub fn is_disabled(game_edition: GameEdition) -> anyhow::Result<Option<String>> {
     tracing::debug!("Checking telemetry servers status");
 
-    for server in game_edition.telemetry_servers() {
-        if crate::check_domain::available(server)? {
+    for (server, available) in crate::check_domain::available_bulk(game_edition.telemetry_servers()) {
+        if available? {
             tracing::warn!("Server is not disabled: {server}");
 
-            return Ok(Some(server.to_string()));
+            return Ok(Some(server));
         }
     }
 
     Ok(None)
 }
+
+/// Get list of the telemetry servers which are already disabled
+#[tracing::instrument(level = "debug")]
+pub fn get_blocked_servers(game_edition: GameEdition) -> Vec<String> {
+    crate::check_domain::available_bulk(game_edition.telemetry_servers())
+        .into_iter()
+        .filter(|(_, available)| !available.as_ref().copied().unwrap_or(true))
+        .map(|(server, _)| server)
+        .collect()
+}
+
+#[inline]
+/// Check whether all the telemetry servers are disabled
+pub fn is_blocked(game_edition: GameEdition) -> bool {
+    get_blocked_servers(game_edition).len() == game_edition.telemetry_servers().len()
+}
@@ -6,13 +6,18 @@ use thiserror::Error;
 use crate::version::Version;
 use crate::traits::version_diff::VersionDiffExt;
 
+#[cfg(feature = "install")]
+use std::sync::Arc;
+
 #[cfg(feature = "install")]
 use crate::installer::{
     downloader::{Downloader, DownloadingError},
     installer::{
         Installer,
+        InstallerError,
         Update as InstallerUpdate
     },
+    lock::{LockFile, LockError},
     free_space
 };
 
@@ -26,6 +31,14 @@ pub enum DiffDownloadingError {
     #[error("{0}")]
     DownloadingError(#[from] DownloadingError),
 
+    /// Installation folder is locked by another process
+    #[error("{0}")]
+    LockError(#[from] LockError),
+
+    /// Failed to install the downloaded archive. Redirected from `Installer`
+    #[error("{0}")]
+    InstallerError(#[from] InstallerError),
+
     /// Installation path wasn't specified. This could happen when you
     /// try to call `install` method on `VersionDiff` that was generated
     /// in `VoicePackage::list_latest`. This method couldn't know
@@ -239,6 +252,10 @@ impl VersionDiffExt for VersionDiff {
 
         let path = path.as_ref();
 
+        // Prevent other processes from installing anything into the same folder.
+        // The lock is released when this variable is dropped
+        let lock = Arc::new(LockFile::new(path)?);
+
         let url = self.downloading_uri().expect("Failed to retreive downloading url");
         let downloaded_size = self.downloaded_size().expect("Failed to retreive downloaded size");
         let unpacked_size = self.unpacked_size().expect("Failed to retreive unpacked size");
@@ -248,7 +265,10 @@ impl VersionDiffExt for VersionDiff {
             .with_temp_folder(self.temp_folder())
 
             // Don't perform space checks in the Installer because we're doing it here
-            .with_free_space_check(false);
+            .with_free_space_check(false)
+
+            // Folder is already locked here
+            .with_lock(lock);
 
         (updater)(InstallerUpdate::CheckingFreeSpace(installer.temp_folder.to_path_buf()));
 
@@ -297,7 +317,7 @@ impl VersionDiffExt for VersionDiff {
         // Install data
         let installer_updater = updater.clone();
 
-        installer.install(path, move |update| (installer_updater)(update));
+        installer.install(path, move |update| (installer_updater)(update))?;
 
         // Create `.version` file here even if hdiff patching is failed because
         // it's easier to explain user why he should run files repairer than
@@ -7,7 +7,7 @@ use crate::pgr::consts::API_DATA_URI;
 pub fn request() -> anyhow::Result<schema::Response> {
     tracing::trace!("Fetching game API");
 
-    Ok(minreq::get(API_DATA_URI)
+    Ok(crate::request(minreq::get(API_DATA_URI))
         .with_timeout(*crate::REQUESTS_TIMEOUT)
         .send()?.json()?)
 }
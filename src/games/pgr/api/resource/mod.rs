@@ -7,7 +7,7 @@ use crate::pgr::consts::API_BASE_URI;
 pub fn request() -> anyhow::Result<schema::Response> {
     tracing::trace!("Fetching resource API");
 
-    Ok(minreq::get(format!("{API_BASE_URI}/{}", super::game::request()?.default.resources))
+    Ok(crate::request(minreq::get(format!("{API_BASE_URI}/{}", super::game::request()?.default.resources)))
         .with_timeout(*crate::REQUESTS_TIMEOUT)
         .send()?.json()?)
 }
@@ -2,8 +2,6 @@ use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
-use md5::{Md5, Digest};
-
 use crate::version::Version;
 use crate::traits::game::GameExt;
 
@@ -158,7 +156,7 @@ impl Game {
                 else if let Ok(metadata) = file_path.metadata() {
                     // And compare updated file size with downloaded one. If they're equal,
                     // then as well compare their md5 hashes if fast_verify = false
-                    if metadata.len() != file.size || (!fast_verify && format!("{:x}", Md5::digest(std::fs::read(file_path)?)).to_ascii_lowercase() != file.md5.to_ascii_lowercase()) {
+                    if metadata.len() != file.size || (!fast_verify && crate::hash::md5_file(&file_path)? != file.md5.to_ascii_lowercase()) {
                         files.push(file.dest.clone());
 
                         // Add only files difference in size to the total download size
@@ -15,11 +15,11 @@ use super::consts::TELEMETRY_SERVERS;
 pub fn is_disabled() -> anyhow::Result<Option<String>> {
     tracing::debug!("Checking telemetry servers status");
 
-    for server in TELEMETRY_SERVERS {
-        if crate::check_domain::available(server)? {
+    for (server, available) in crate::check_domain::available_bulk(TELEMETRY_SERVERS) {
+        if available? {
             tracing::warn!("Server is not disabled: {server}");
 
-            return Ok(Some(server.to_string()));
+            return Ok(Some(server));
         }
     }
 
@@ -14,6 +14,7 @@ use crate::traits::version_diff::VersionDiffExt;
 #[cfg(feature = "install")]
 use crate::installer::{
     downloader::DownloadingError,
+    lock::{LockFile, LockError},
     free_space
 };
 
@@ -27,6 +28,10 @@ pub enum DiffDownloadingError {
     #[error("{0}")]
     DownloadingError(#[from] DownloadingError),
 
+    /// Installation folder is locked by another process
+    #[error("{0}")]
+    LockError(#[from] LockError),
+
     /// Installation path wasn't specified. This could happen when you
     /// try to call `install` method on `VersionDiff` that was generated
     /// in `VoicePackage::list_latest`. This method couldn't know
@@ -217,6 +222,10 @@ impl VersionDiffExt for VersionDiff {
 
         let path = path.as_ref();
 
+        // Prevent other processes from installing anything into the same folder.
+        // The lock is released when this variable is dropped
+        let _lock = LockFile::new(path)?;
+
         let url = self.downloading_uri().expect("Failed to retreive downloading url");
         let required = self.unpacked_size().expect("Failed to retreive total size");
         let files = self.files().expect("Failed to retreive list of files for downloading");
@@ -37,6 +37,11 @@ impl GameEdition {
         concat!("Sta", "rRai", "l_Data")
     }
 
+    #[inline]
+    pub fn executable_name(&self) -> &str {
+        concat!("Sta", "rRai", "l.exe")
+    }
+
     #[inline]
     pub fn telemetry_servers(&self) -> &[&str] {
         match self {
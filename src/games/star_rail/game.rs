@@ -2,6 +2,8 @@ use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 
+use serde::{Serialize, Deserialize};
+
 use crate::version::Version;
 use crate::traits::game::GameExt;
 
@@ -12,6 +14,20 @@ use super::version_diff::*;
 use super::voice_data::locale::VoiceLocale;
 use super::voice_data::package::VoicePackage;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InstallationCompleteness {
+    /// Both game data folder and executable are present
+    Complete,
+
+    /// Game data folder exists but the executable is missing
+    PartialMissingBinary,
+
+    /// Game executable exists but the data folder is missing
+    PartialMissingData,
+
+    NotInstalled
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Game {
     path: PathBuf,
@@ -39,6 +55,19 @@ impl GameExt for Game {
         self.edition
     }
 
+    #[inline]
+    fn executable_name(&self) -> Option<&str> {
+        Some(self.edition.executable_name())
+    }
+
+    #[inline]
+    /// Checks if the game is installed
+    ///
+    /// Partially installed game (e.g. without the executable) is considered not installed
+    fn is_installed(&self) -> bool {
+        self.completeness() == InstallationCompleteness::Complete
+    }
+
     #[tracing::instrument(level = "trace", ret)]
     /// Try to get latest game version
     fn get_latest_version(edition: GameEdition) -> anyhow::Result<Version> {
@@ -129,6 +158,19 @@ impl GameExt for Game {
 }
 
 impl Game {
+    /// Check which parts of the game installation are present
+    pub fn completeness(&self) -> InstallationCompleteness {
+        let data = self.path.join(self.edition.data_folder()).exists();
+        let binary = self.path.join(self.edition.executable_name()).exists();
+
+        match (data, binary) {
+            (true, true)   => InstallationCompleteness::Complete,
+            (true, false)  => InstallationCompleteness::PartialMissingBinary,
+            (false, true)  => InstallationCompleteness::PartialMissingData,
+            (false, false) => InstallationCompleteness::NotInstalled
+        }
+    }
+
     /// Get list of installed voice packages
     pub fn get_voice_packages(&self) -> anyhow::Result<Vec<VoicePackage>> {
         let content = std::fs::read_dir(get_voice_packages_path(&self.path, self.edition))?;
@@ -11,7 +11,10 @@ pub mod repairer;
 pub mod prelude {
     pub use super::consts::*;
     pub use super::version_diff::*;
-    pub use super::game::Game;
+    pub use super::game::{
+        Game,
+        InstallationCompleteness
+    };
     pub use super::voice_data::prelude::*;
     pub use super::telemetry;
 
@@ -1,6 +1,8 @@
 pub mod locale;
 pub mod package;
 
+pub use package::get_installed_languages;
+
 pub mod prelude {
     pub use super::locale::VoiceLocale;
     pub use super::package::VoicePackage;
@@ -181,6 +181,16 @@ impl VoicePackage {
         }
     }
 
+    /// Get voice package archive size in bytes from the game API
+    ///
+    /// Return `None` if the package is installed or its size is unknown
+    pub fn download_size(&self) -> Option<u64> {
+        match self {
+            VoicePackage::Installed { .. } => None,
+            VoicePackage::NotInstalled { data, .. } => data.size.parse::<u64>().ok()
+        }
+    }
+
     #[inline]
     /// This method will return `true` if the package has `VoicePackage::Installed` enum value
     ///
@@ -470,3 +480,53 @@ impl VoicePackage {
         }
     }
 }
+
+/// Get list of the voice languages selected in the game
+///
+/// Languages are read from the game's `Persistent/AudioLaucherRecord.txt` file
+/// instead of checking voice packages folders existence. If the file doesn't exist
+/// an empty list is returned
+#[tracing::instrument(level = "debug", ret)]
+pub fn get_installed_languages(game_path: impl AsRef<Path> + std::fmt::Debug, game_edition: GameEdition) -> Vec<VoiceLocale> {
+    let record = game_path.as_ref()
+        .join(game_edition.data_folder())
+        .join("Persistent/AudioLaucherRecord.txt");
+
+    match std::fs::read(record) {
+        Ok(record) => parse_audio_record(&String::from_utf8_lossy(&record)),
+        Err(_) => Vec::new()
+    }
+}
+
+/// Get voice locales mentioned in the audio record file
+fn parse_audio_record(record: &str) -> Vec<VoiceLocale> {
+    let mut locales = Vec::new();
+
+    let words = record.split(|c: char| !c.is_ascii_alphanumeric() && c != '(' && c != ')' && c != '-');
+
+    for word in words {
+        if let Some(locale) = VoiceLocale::from_str(word) {
+            if !locales.contains(&locale) {
+                locales.push(locale);
+            }
+        }
+    }
+
+    locales
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_audio_record() {
+        assert_eq!(parse_audio_record("English\r\nChinese(PRC)\r\nEnglish\r\n"), vec![
+            VoiceLocale::English,
+            VoiceLocale::Chinese
+        ]);
+
+        assert_eq!(parse_audio_record("{\"Japanese\": 1, \"Other\": 2}"), vec![VoiceLocale::Japanese]);
+        assert!(parse_audio_record("").is_empty());
+    }
+}
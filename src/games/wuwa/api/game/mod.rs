@@ -1,5 +1,7 @@
 use std::io::Read;
+use std::time::Duration;
 
+use crate::disk_cache::DiskCache;
 use crate::wuwa::consts::GameEdition;
 
 pub mod schema;
@@ -7,12 +9,35 @@ pub mod schema;
 #[cached::proc_macro::cached(result)]
 #[tracing::instrument(level = "trace")]
 pub fn request(edition: GameEdition) -> anyhow::Result<schema::Response> {
+    request_with_timeout(edition, None)
+}
+
+#[tracing::instrument(level = "trace")]
+/// Fetch game API using specified timeout instead of `REQUESTS_TIMEOUT`
+///
+/// Unlike `request` the response is not cached
+pub fn request_with_timeout(edition: GameEdition, timeout: Option<Duration>) -> anyhow::Result<schema::Response> {
     tracing::trace!("Fetching game API");
 
-    let response = minreq::get(edition.api_uri())
-        .with_timeout(*crate::REQUESTS_TIMEOUT)
+    let response = crate::request(minreq::get(edition.api_uri()))
+        .with_timeout(crate::requests_timeout(timeout))
         .send()?;
 
+    parse_response(&response)
+}
+
+#[tracing::instrument(level = "trace", skip(cache))]
+/// Fetch game API using the disk cache
+///
+/// Timeout falls back to `REQUESTS_TIMEOUT` if not specified
+pub fn request_with_disk_cache(edition: GameEdition, cache: &DiskCache<schema::Response>, timeout: Option<Duration>) -> anyhow::Result<schema::Response> {
+    tracing::trace!("Fetching game API");
+
+    cache.get_with(edition.api_uri(), crate::requests_timeout(timeout), parse_response)
+}
+
+/// Decompress and parse game API response
+fn parse_response(response: &minreq::Response) -> anyhow::Result<schema::Response> {
     let json = match response.headers.get("content-encoding").map(String::as_str) {
         Some("gzip") => flate2::read::GzDecoder::new(response.as_bytes())
             .bytes()
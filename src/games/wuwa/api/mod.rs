@@ -1,3 +1,5 @@
+use std::time::{Duration, Instant};
+
 use crate::wuwa::consts::GameEdition;
 
 pub mod game;
@@ -9,8 +11,21 @@ pub mod resource;
 pub fn find_cdn_uri(edition: GameEdition) -> anyhow::Result<String> {
     tracing::trace!("Finding CDN address");
 
-    let api = game::request(edition)?.default;
+    get_cdn_uri(&game::request(edition)?.default)
+}
+
+#[tracing::instrument(level = "trace")]
+/// Find CDN link using specified timeout instead of `REQUESTS_TIMEOUT`
+///
+/// Unlike `find_cdn_uri` the result is not cached
+pub fn find_cdn_uri_with_timeout(edition: GameEdition, timeout: Option<Duration>) -> anyhow::Result<String> {
+    tracing::trace!("Finding CDN address");
 
+    get_cdn_uri(&game::request_with_timeout(edition, timeout)?.default)
+}
+
+/// Get CDN link with the highest priority from the game API response
+pub(crate) fn get_cdn_uri(api: &game::schema::Data) -> anyhow::Result<String> {
     let cdn = api.cdnList.iter()
         .min_by(|a, b| a.P.cmp(&b.P));
 
@@ -20,3 +35,66 @@ pub fn find_cdn_uri(edition: GameEdition) -> anyhow::Result<String> {
 
     Ok(cdn.url.strip_suffix('/').unwrap().to_string())
 }
+
+#[cached::proc_macro::cached(result)]
+#[tracing::instrument]
+/// Find CDN link with the lowest latency from the API response
+///
+/// HEAD request is sent to every CDN at the same time
+pub fn find_cdn_uri_fastest(edition: GameEdition) -> anyhow::Result<String> {
+    find_cdn_uri_fastest_with_timeout(edition, None)
+}
+
+#[tracing::instrument(level = "trace")]
+/// Find CDN link with the lowest latency using specified timeout instead of `REQUESTS_TIMEOUT`
+///
+/// Unlike `find_cdn_uri_fastest` the result is not cached
+pub fn find_cdn_uri_fastest_with_timeout(edition: GameEdition, timeout: Option<Duration>) -> anyhow::Result<String> {
+    tracing::trace!("Measuring CDN latencies");
+
+    let api = game::request_with_timeout(edition, timeout)?.default;
+
+    let handles = api.cdnList.into_iter()
+        .map(|cdn| {
+            let uri = cdn.url.trim_end_matches('/').to_string();
+
+            std::thread::spawn(move || {
+                let time = Instant::now();
+
+                let response = crate::request(minreq::head(&uri))
+                    .with_timeout(crate::requests_timeout(timeout))
+                    .send();
+
+                // CDN base urls usually respond to HEAD with 403 or 404,
+                // but any response except server errors means it's reachable
+                match response {
+                    Ok(response) if response.status_code < 500 => Some((uri, time.elapsed())),
+
+                    Ok(response) => {
+                        tracing::warn!("CDN {uri} responded with server error {}", response.status_code);
+
+                        None
+                    },
+
+                    Err(err) => {
+                        tracing::warn!("Failed to reach CDN {uri}: {err}");
+
+                        None
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let fastest = handles.into_iter()
+        .flat_map(|handle| handle.join().ok().flatten())
+        .min_by_key(|(_, latency)| *latency);
+
+    let Some((uri, latency)) = fastest else {
+        anyhow::bail!("Failed to find available game CDN link");
+    };
+
+    tracing::debug!(?latency, "Fastest CDN: {uri}");
+
+    Ok(uri)
+}
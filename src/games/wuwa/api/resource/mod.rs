@@ -1,21 +1,31 @@
 use std::io::Read;
+use std::time::Duration;
 
 use crate::wuwa::consts::GameEdition;
 
-use super::find_cdn_uri;
-
 pub mod schema;
 
 #[cached::proc_macro::cached(result)]
 #[tracing::instrument(level = "trace")]
 pub fn request(edition: GameEdition) -> anyhow::Result<schema::Response> {
+    request_with_timeout(edition, None)
+}
+
+#[tracing::instrument(level = "trace")]
+/// Fetch resource API using specified timeout instead of `REQUESTS_TIMEOUT`
+///
+/// Unlike `request` the response is not cached
+pub fn request_with_timeout(edition: GameEdition, timeout: Option<Duration>) -> anyhow::Result<schema::Response> {
     tracing::trace!("Fetching resource API");
 
-    let cdn = find_cdn_uri(edition)?;
-    let resources = super::game::request(edition)?.default.resources;
+    // Fetch game API only once to not double the timeout
+    let api = super::game::request_with_timeout(edition, timeout)?.default;
+
+    let cdn = super::get_cdn_uri(&api)?;
+    let resources = api.resources;
 
-    let response = minreq::get(format!("{cdn}/{resources}"))
-        .with_timeout(*crate::REQUESTS_TIMEOUT)
+    let response = crate::request(minreq::get(format!("{cdn}/{resources}")))
+        .with_timeout(crate::requests_timeout(timeout))
         .send()?;
 
     let json = match response.headers.get("content-encoding").map(String::as_str) {
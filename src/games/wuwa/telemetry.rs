@@ -16,11 +16,11 @@ use super::consts::GameEdition;
 pub fn is_disabled(game_edition: GameEdition) -> anyhow::Result<Option<String>> {
     tracing::debug!("Checking telemetry servers status");
 
-    for server in game_edition.telemetry_servers() {
-        if crate::check_domain::available(server)? {
+    for (server, available) in crate::check_domain::available_bulk(game_edition.telemetry_servers()) {
+        if available? {
             tracing::warn!("Server is not disabled: {server}");
 
-            return Ok(Some(server.to_string()));
+            return Ok(Some(server));
         }
     }
 
@@ -2,9 +2,30 @@ use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Response {
-    pub retcode: u16,
+    pub retcode: i32,
     pub message: String,
-    pub data: Data
+
+    /// Can be `null` if the API returned an error
+    pub data: Option<Data>
+}
+
+impl crate::api::GamesResponse for Response {
+    type Package = GamePackage;
+
+    #[inline]
+    fn retcode(&self) -> i32 {
+        self.retcode
+    }
+
+    #[inline]
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    #[inline]
+    fn into_packages(self) -> Option<Vec<GamePackage>> {
+        self.data.map(|data| data.game_packages)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
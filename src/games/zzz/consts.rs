@@ -1,5 +1,9 @@
+use std::path::{Path, PathBuf};
+
 use serde::{Serialize, Deserialize};
 
+use super::voice_data::locale::VoiceLocale;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum GameEdition {
     Global,
@@ -32,6 +36,11 @@ impl GameEdition {
         concat!("Zen", "lessZ", "oneZero_Data")
     }
 
+    #[inline]
+    pub fn executable_name(&self) -> &str {
+        concat!("Zen", "lessZ", "oneZero.exe")
+    }
+
     #[inline]
     pub fn telemetry_servers(&self) -> &[&str] {
         match self {
@@ -64,3 +73,15 @@ impl GameEdition {
         }
     }
 }
+
+#[inline]
+pub fn get_voice_packages_path<T: AsRef<Path>>(game_path: T, game_edition: GameEdition) -> PathBuf {
+    game_path.as_ref()
+        .join(game_edition.data_folder())
+        .join("StreamingAssets/Audio/Windows/Full")
+}
+
+#[inline]
+pub fn get_voice_package_path<T: AsRef<Path>>(game_path: T, game_edition: GameEdition, locale: VoiceLocale) -> PathBuf {
+    get_voice_packages_path(game_path, game_edition).join(locale.to_folder())
+}
@@ -2,6 +2,7 @@ pub mod consts;
 pub mod api;
 pub mod version_diff;
 pub mod game;
+pub mod voice_data;
 pub mod telemetry;
 
 #[cfg(feature = "install")]
@@ -11,6 +12,7 @@ pub mod prelude {
     pub use super::consts::*;
     pub use super::version_diff::*;
     pub use super::game::Game;
+    pub use super::voice_data::prelude::*;
     pub use super::telemetry;
 
     #[cfg(feature = "install")]
@@ -14,6 +14,7 @@ use crate::{
     installer::{
         downloader::{Downloader, DownloadingError},
         installer::Update as InstallerUpdate,
+        lock::{LockFile, LockError},
         free_space,
         archives::Archive
     },
@@ -61,6 +62,10 @@ pub enum DiffDownloadingError {
     #[error("{0}")]
     DownloadingError(#[from] DownloadingError),
 
+    /// Installation folder is locked by another process
+    #[error("{0}")]
+    LockError(#[from] LockError),
+
     /// Failed to apply hdiff patch
     #[error("Failed to apply hdiff patch: {0}")]
     HdiffPatch(String),
@@ -359,6 +364,10 @@ impl VersionDiffExt for VersionDiff {
         };
 
         let path = path.as_ref().to_path_buf();
+
+        // Prevent other processes from installing anything into the same folder.
+        // The lock is released when this variable is dropped
+        let _lock = LockFile::new(&path)?;
         let temp_folder = self.temp_folder();
 
         let downloaded_size = self.downloaded_size().expect("Failed to retrieve downloaded size");
@@ -0,0 +1,92 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VoiceLocale {
+    English,
+    Japanese,
+    Korean,
+    Chinese
+}
+
+impl VoiceLocale {
+    #[inline]
+    pub fn list() -> &'static [VoiceLocale] {
+        &[Self::English, Self::Japanese, Self::Korean, Self::Chinese]
+    }
+
+    /// Convert enum value to its name
+    /// 
+    /// `VoiceLocale::English` -> `English`
+    #[inline]
+    pub fn to_name(&self) -> &str {
+        match self {
+            Self::English  => "English",
+            Self::Japanese => "Japanese",
+            Self::Korean   => "Korean",
+            Self::Chinese  => "Chinese"
+        }
+    }
+
+    /// Convert enum value to its code
+    /// 
+    /// `VoiceLocale::English` -> `en-us`
+    #[inline]
+    pub fn to_code(&self) -> &str {
+        match self {
+            Self::English  => "en-us",
+            Self::Japanese => "ja-jp",
+            Self::Korean   => "ko-kr",
+            Self::Chinese  => "zh-cn"
+        }
+    }
+
+    /// Convert enum value to its folder name
+    /// 
+    /// `VoiceLocale::English` -> `En`
+    #[inline]
+    pub fn to_folder(&self) -> &str {
+        match self {
+            Self::English  => "En",
+            Self::Japanese => "Jp",
+            Self::Korean   => "Kr",
+            Self::Chinese  => "Cn"
+        }
+    }
+
+    /// Try to convert string to enum
+    /// 
+    /// - `English` -> `VoiceLocale::English`
+    /// - `En` -> `VoiceLocale::English`
+    /// - `en-us` -> `VoiceLocale::English`
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str<T: AsRef<str>>(str: T) -> Option<Self> {
+        match str.as_ref() {
+            // Locales names
+            "English"  => Some(Self::English),
+            "Japanese" => Some(Self::Japanese),
+            "Korean"   => Some(Self::Korean),
+            "Chinese"  => Some(Self::Chinese),
+
+            // Lowercased variants
+            "english"  => Some(Self::English),
+            "japanese" => Some(Self::Japanese),
+            "korean"   => Some(Self::Korean),
+            "chinese"  => Some(Self::Chinese),
+
+            // Folders
+            "En" => Some(Self::English),
+            "Jp" => Some(Self::Japanese),
+            "Kr" => Some(Self::Korean),
+            "Cn" => Some(Self::Chinese),
+
+            // Codes
+            "en-us" => Some(Self::English),
+            "ja-jp" => Some(Self::Japanese),
+            "ko-kr" => Some(Self::Korean),
+            "zh-cn" => Some(Self::Chinese),
+
+            _ => None
+        }
+    }
+}
@@ -0,0 +1,391 @@
+use std::path::{Path, PathBuf};
+
+use fs_extra::dir::get_size;
+
+use crate::version::Version;
+use crate::traits::game::GameExt;
+
+use crate::zzz::api::{
+    self,
+    schema::AudioPackage
+};
+
+use crate::zzz::consts::*;
+use crate::zzz::game::Game;
+use crate::zzz::voice_data::locale::VoiceLocale;
+
+#[cfg(feature = "install")]
+use crate::zzz::version_diff::*;
+
+/// Find voice package with specified locale from list of packages
+fn find_voice_pack(list: Vec<AudioPackage>, locale: VoiceLocale) -> AudioPackage {
+    for pack in list {
+        if pack.language == locale.to_code() {
+            return pack;
+        }
+    }
+
+    // We're sure that all possible voice packages are listed in VoiceLocale... right?
+    unreachable!();
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VoicePackage {
+    Installed {
+        path: PathBuf,
+        locale: VoiceLocale,
+        game_edition: GameEdition
+    },
+
+    NotInstalled {
+        locale: VoiceLocale,
+        version: Version,
+        data: AudioPackage,
+        game_path: Option<PathBuf>,
+        game_edition: GameEdition
+    }
+}
+
+impl VoicePackage {
+    /// Voice packages can't be instaled wherever you want.
+    /// Thus this method can return `None` in case the path
+    /// doesn't point to a real voice package folder
+    pub fn new<T: Into<PathBuf>>(path: T, game_edition: GameEdition) -> Option<Self> {
+        let path = path.into();
+
+        if path.exists() && path.is_dir() {
+            let locale = VoiceLocale::from_str(path.file_name()?.to_string_lossy())?;
+
+            Some(Self::Installed {
+                path,
+                locale,
+                game_edition
+            })
+        }
+
+        else {
+            None
+        }
+    }
+
+    /// Get latest voice package with specified locale
+    ///
+    /// Note that returned object will be `VoicePackage::NotInstalled`, but
+    /// technically it can be installed. This method just don't know the game's path
+    pub fn with_locale(locale: VoiceLocale, game_edition: GameEdition) -> anyhow::Result<Self> {
+        let latest = api::request(game_edition)?.main.major;
+
+        Ok(Self::NotInstalled {
+            locale,
+            version: Version::from_str(latest.version).unwrap(),
+            data: find_voice_pack(latest.audio_pkgs, locale),
+            game_path: None,
+            game_edition
+        })
+    }
+
+    #[inline]
+    pub fn game_edition(&self) -> GameEdition {
+        match self {
+            Self::Installed { game_edition, .. } |
+            Self::NotInstalled { game_edition, .. } => *game_edition
+        }
+    }
+
+    // TODO: find_in(game_path: String, locale: VoiceLocale)
+
+    #[inline]
+    /// Get installation status of this package
+    ///
+    /// This method will return `false` if this package is `VoicePackage::NotInstalled` enum value
+    ///
+    /// If you want to check it's actually installed - you'd need to use `is_installed_in`
+    pub fn is_installed(&self) -> bool {
+        match self {
+            Self::Installed { .. } => true,
+            Self::NotInstalled { .. } => false
+        }
+    }
+
+    /// Calculate voice package size in bytes
+    ///
+    /// (unpacked size, Option(archive size))
+    pub fn size(&self) -> (u64, Option<u64>) {
+        match self {
+            VoicePackage::Installed { path, .. } => (get_size(path).unwrap(), None),
+            VoicePackage::NotInstalled { data, .. } => (
+                data.decompressed_size.parse::<u64>().unwrap(),
+                Some(data.size.parse::<u64>().unwrap())
+            ),
+        }
+    }
+
+    /// Get voice package archive size in bytes from the game API
+    ///
+    /// Return `None` if the package is installed or its size is unknown
+    pub fn download_size(&self) -> Option<u64> {
+        match self {
+            VoicePackage::Installed { .. } => None,
+            VoicePackage::NotInstalled { data, .. } => data.size.parse::<u64>().ok()
+        }
+    }
+
+    #[inline]
+    /// This method will return `true` if the package has `VoicePackage::Installed` enum value
+    ///
+    /// If it's `VoicePackage::NotInstalled`, then this method will check `game_path`'s voices folder
+    pub fn is_installed_in<T: AsRef<Path>>(&self, game_path: T) -> bool {
+        match self {
+            Self::Installed { .. } => true,
+            Self::NotInstalled { locale, .. } => get_voice_package_path(game_path, self.game_edition(), *locale).exists()
+        }
+    }
+
+    /// Get list of latest voice packages
+    pub fn list_latest(game_edition: GameEdition) -> anyhow::Result<Vec<VoicePackage>> {
+        let response = api::request(game_edition)?;
+
+        let mut packages = Vec::new();
+        let version = Version::from_str(response.main.major.version).unwrap();
+
+        for package in response.main.major.audio_pkgs {
+            packages.push(Self::NotInstalled {
+                locale: VoiceLocale::from_str(&package.language).unwrap(),
+                version,
+                data: package,
+                game_path: None,
+                game_edition
+            });
+        }
+
+        Ok(packages)
+    }
+
+    #[inline]
+    /// Get voice package locale
+    pub fn locale(&self) -> VoiceLocale {
+        match self {
+            Self::Installed { locale, .. } |
+            Self::NotInstalled { locale, .. } => *locale
+        }
+    }
+
+    #[tracing::instrument(level = "debug", ret)]
+    /// This method can fail to parse this package version.
+    /// It also can mean that the corresponding folder doesn't
+    /// contain voice package files
+    pub fn try_get_version(&self) -> anyhow::Result<Version> {
+        tracing::debug!("Trying to get {} voice package version", self.locale().to_code());
+
+        match &self {
+            Self::NotInstalled { version, .. } => Ok(*version),
+            Self::Installed { path, game_edition, .. } => {
+                match std::fs::read(path.join(".version")) {
+                    Ok(curr) => {
+                        tracing::debug!("Found .version file: {}.{}.{}", curr[0], curr[1], curr[2]);
+
+                        Ok(Version::new(curr[0], curr[1], curr[2]))
+                    },
+
+                    // There's no voice packages sizes history for this game
+                    // so we assume the package was installed together with the game
+                    // and has the same version. This file will be properly created
+                    // in the install method
+                    Err(_) => {
+                        tracing::debug!(".version file wasn't found. Using installed game version");
+
+                        let Some(game_path) = path.ancestors().nth(6) else {
+                            tracing::error!("Failed to find game directory");
+
+                            anyhow::bail!("Failed to find game directory");
+                        };
+
+                        Game::new(game_path, *game_edition).get_version()
+                    }
+                }
+            }
+        }
+    }
+
+    #[tracing::instrument(level = "trace", ret)]
+    /// Try to delete voice package
+    ///
+    /// FIXME:
+    /// ⚠️ May fail on Chinese version due to paths differences
+    pub fn delete(&self) -> anyhow::Result<()> {
+        tracing::trace!("Deleting {} voice package", self.locale().to_code());
+
+        match self {
+            VoicePackage::Installed { path, .. } => {
+                let mut game_path = path.clone();
+
+                for _ in 0..6 {
+                    game_path = match game_path.parent() {
+                        Some(game_path) => game_path.into(),
+                        None => {
+                            tracing::error!("Failed to find game directory");
+
+                            return Err(anyhow::anyhow!("Failed to find game directory"))
+                        }
+                    };
+                }
+
+                self.delete_in(game_path)
+            }
+
+            VoicePackage::NotInstalled { game_path, .. } => {
+                match game_path {
+                    Some(game_path) => self.delete_in(game_path),
+                    None => {
+                        tracing::error!("Failed to find game directory");
+
+                        return Err(anyhow::anyhow!("Failed to find game directory"))
+                    }
+                }
+            }
+        }
+    }
+
+    #[tracing::instrument(level = "debug", ret)]
+    /// Try to delete voice package from specific game directory
+    ///
+    /// FIXME:
+    /// ⚠️ May fail on Chinese version due to paths differences
+    pub fn delete_in<T: Into<PathBuf> + std::fmt::Debug>(&self, game_path: T) -> anyhow::Result<()> {
+        let game_path = game_path.into();
+        let locale = self.locale();
+
+        tracing::debug!("Deleting {} voice package", locale.to_code());
+
+        std::fs::remove_dir_all(get_voice_package_path(&game_path, self.game_edition(), locale))?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "install")]
+    #[tracing::instrument(level = "debug", ret)]
+    pub fn try_get_diff(&self) -> anyhow::Result<VersionDiff> {
+        tracing::debug!("Trying to find version diff for {} voice package", self.locale().to_code());
+
+        let game_edition = self.game_edition();
+        let response = api::request(game_edition)?;
+
+        if self.is_installed() {
+            let current = self.try_get_version()?;
+
+            if response.main.major.version == current {
+                tracing::debug!("Package version is latest");
+
+                // If we're running latest game version the diff we need to download
+                // must always be `predownload.diffs[0]`, but just to be safe I made
+                // a loop through possible variants, and if none of them was correct
+                // (which is not possible in reality) we should just say thath the game
+                // is latest
+                if let Some(predownload_info) = response.pre_download {
+                    if let Some(predownload_major) = predownload_info.major {
+                        for diff in predownload_info.patches {
+                            if diff.version == current {
+                                let diff = find_voice_pack(diff.audio_pkgs, self.locale());
+
+                                return Ok(VersionDiff::Predownload {
+                                    current,
+                                    latest: Version::from_str(predownload_major.version).unwrap(),
+                                    uri: diff.url,
+
+                                    downloaded_size: diff.size.parse::<u64>().unwrap(),
+                                    unpacked_size: diff.decompressed_size.parse::<u64>().unwrap(),
+
+                                    installation_path: match self {
+                                        VoicePackage::Installed { .. } => None,
+                                        VoicePackage::NotInstalled { game_path, .. } => game_path.clone()
+                                    },
+
+                                    version_file_path: match self {
+                                        VoicePackage::Installed { path, .. } => Some(path.join(".version")),
+                                        VoicePackage::NotInstalled { game_path, .. } => game_path.as_ref()
+                                            .map(|game_path| get_voice_package_path(game_path, game_edition, self.locale()).join(".version"))
+                                    },
+
+                                    temp_folder: None,
+                                    edition: game_edition
+                                })
+                            }
+                        }
+                    }
+                }
+
+                Ok(VersionDiff::Latest {
+                    version: current,
+                    edition: game_edition
+                })
+            }
+
+            else {
+                tracing::debug!("Package is outdated: {} -> {}", current, response.main.major.version);
+
+                for diff in response.main.patches {
+                    if diff.version == current {
+                        let diff = find_voice_pack(diff.audio_pkgs, self.locale());
+
+                        return Ok(VersionDiff::Diff {
+                            current,
+                            latest: Version::from_str(response.main.major.version).unwrap(),
+                            uri: diff.url,
+
+                            downloaded_size: diff.size.parse::<u64>().unwrap(),
+                            unpacked_size: diff.decompressed_size.parse::<u64>().unwrap(),
+
+                            installation_path: match self {
+                                VoicePackage::Installed { .. } => None,
+                                VoicePackage::NotInstalled { game_path, .. } => game_path.clone()
+                            },
+
+                            version_file_path: match self {
+                                VoicePackage::Installed { path, .. } => Some(path.join(".version")),
+                                VoicePackage::NotInstalled { game_path, .. } => game_path.as_ref()
+                                    .map(|game_path| get_voice_package_path(game_path, game_edition, self.locale()).join(".version"))
+                            },
+
+                            temp_folder: None,
+                            edition: game_edition
+                        })
+                    }
+                }
+
+                Ok(VersionDiff::Outdated {
+                    current,
+                    latest: Version::from_str(response.main.major.version).unwrap(),
+                    edition: game_edition
+                })
+            }
+        }
+
+        else {
+            tracing::debug!("Package is not installed");
+
+            let latest = find_voice_pack(response.main.major.audio_pkgs, self.locale());
+
+            Ok(VersionDiff::NotInstalled {
+                latest: Version::from_str(response.main.major.version).unwrap(),
+                segments_uris: vec![latest.url],
+
+                downloaded_size: latest.size.parse::<u64>().unwrap(),
+                unpacked_size: latest.decompressed_size.parse::<u64>().unwrap(),
+
+                installation_path: match self {
+                    VoicePackage::Installed { .. } => None,
+                    VoicePackage::NotInstalled { game_path, .. } => game_path.clone()
+                },
+
+                version_file_path: match self {
+                    VoicePackage::Installed { path, .. } => Some(path.join(".version")),
+                    VoicePackage::NotInstalled { game_path, .. } => game_path.as_ref()
+                        .map(|game_path| get_voice_package_path(game_path, game_edition, self.locale()).join(".version"))
+                },
+
+                temp_folder: None,
+                edition: game_edition
+            })
+        }
+    }
+}
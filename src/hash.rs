@@ -0,0 +1,173 @@
+use std::path::Path;
+use std::fs::File;
+use std::io::Read;
+
+use serde::{Serialize, Deserialize};
+
+use md5::{Md5, Digest};
+use sha2::Sha256;
+use xxhash_rust::xxh64::{xxh64, Xxh64};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HashAlgorithm {
+    Md5,
+    Sha256,
+    Blake3,
+    XxHash64
+}
+
+impl Default for HashAlgorithm {
+    #[inline]
+    fn default() -> Self {
+        Self::Md5
+    }
+}
+
+/// Calculate md5 hash of the data
+///
+/// Returns lowercased hex string
+#[inline]
+pub fn md5_str(data: impl AsRef<[u8]>) -> String {
+    format!("{:x}", Md5::digest(data))
+}
+
+/// Calculate sha256 hash of the data
+///
+/// Returns lowercased hex string
+#[inline]
+pub fn sha256_str(data: impl AsRef<[u8]>) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Calculate blake3 hash of the data
+///
+/// Returns lowercased hex string
+#[inline]
+pub fn blake3_str(data: impl AsRef<[u8]>) -> String {
+    blake3::hash(data.as_ref()).to_hex().to_string()
+}
+
+/// Calculate md5 hash of the file
+///
+/// The file is read in chunks so it's not loaded in memory entirely
+pub fn md5_file(path: impl AsRef<Path>) -> std::io::Result<String> {
+    let mut hasher = Md5::new();
+
+    std::io::copy(&mut File::open(path)?, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Calculate sha256 hash of the file
+///
+/// The file is read in chunks so it's not loaded in memory entirely
+pub fn sha256_file(path: impl AsRef<Path>) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+
+    std::io::copy(&mut File::open(path)?, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Calculate xxhash64 hash of the data with zero seed
+///
+/// Returns lowercased hex string
+#[inline]
+pub fn xxhash64_str(data: impl AsRef<[u8]>) -> String {
+    format!("{:016x}", xxh64(data.as_ref(), 0))
+}
+
+/// Calculate hash of the data using specified algorithm
+///
+/// Returns lowercased hex string
+pub fn hash_str(data: impl AsRef<[u8]>, algorithm: HashAlgorithm) -> String {
+    match algorithm {
+        HashAlgorithm::Md5      => md5_str(data),
+        HashAlgorithm::Sha256   => sha256_str(data),
+        HashAlgorithm::Blake3   => blake3_str(data),
+        HashAlgorithm::XxHash64 => xxhash64_str(data)
+    }
+}
+
+/// Calculate hash of the file using specified algorithm
+///
+/// The file is read in chunks so it's not loaded in memory entirely
+pub fn hash_file(path: impl AsRef<Path>, algorithm: HashAlgorithm) -> std::io::Result<String> {
+    match algorithm {
+        HashAlgorithm::Md5    => md5_file(path),
+        HashAlgorithm::Sha256 => sha256_file(path),
+
+        HashAlgorithm::Blake3 => {
+            let mut hasher = blake3::Hasher::new();
+
+            std::io::copy(&mut File::open(path)?, &mut hasher)?;
+
+            Ok(hasher.finalize().to_hex().to_string())
+        }
+
+        HashAlgorithm::XxHash64 => {
+            let mut file = File::open(path)?;
+            let mut hasher = Xxh64::new(0);
+            let mut buf = [0; 64 * 1024];
+
+            loop {
+                let read = file.read(&mut buf)?;
+
+                if read == 0 {
+                    break;
+                }
+
+                hasher.update(&buf[..read]);
+            }
+
+            Ok(format!("{:016x}", hasher.digest()))
+        }
+    }
+}
+
+#[inline]
+/// Check that the data has expected hash. Hashes are compared case-insensitively
+pub fn verify_bytes(data: impl AsRef<[u8]>, expected: impl AsRef<str>, algorithm: HashAlgorithm) -> bool {
+    hash_str(data, algorithm) == expected.as_ref().to_ascii_lowercase()
+}
+
+#[inline]
+/// Check that the file has expected hash. Hashes are compared case-insensitively
+pub fn verify_file(path: impl AsRef<Path>, expected: impl AsRef<str>, algorithm: HashAlgorithm) -> std::io::Result<bool> {
+    Ok(hash_file(path, algorithm)? == expected.as_ref().to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_hashes() {
+        assert_eq!(hash_str(b"", HashAlgorithm::Md5), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(hash_str(b"", HashAlgorithm::Sha256), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(hash_str(b"", HashAlgorithm::Blake3), "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262");
+        assert_eq!(hash_str(b"", HashAlgorithm::XxHash64), "ef46db3751d8e999");
+    }
+
+    #[test]
+    fn test_verify_bytes() {
+        assert!(verify_bytes(b"", "D41D8CD98F00B204E9800998ECF8427E", HashAlgorithm::Md5));
+        assert!(!verify_bytes(b"amogus", "d41d8cd98f00b204e9800998ecf8427e", HashAlgorithm::Md5));
+        assert!(!verify_bytes(b"", "d41d8cd98f00b204e9800998ecf8427e", HashAlgorithm::Sha256));
+    }
+
+    #[test]
+    fn test_hash_file() -> std::io::Result<()> {
+        let path = std::env::temp_dir().join(".anime-game-core-test-hash-file");
+
+        std::fs::write(&path, b"Hello, World!")?;
+
+        for algorithm in [HashAlgorithm::Md5, HashAlgorithm::Sha256, HashAlgorithm::Blake3, HashAlgorithm::XxHash64] {
+            assert_eq!(hash_file(&path, algorithm)?, hash_str(b"Hello, World!", algorithm));
+        }
+
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+}
@@ -1,16 +1,21 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf, Component};
 use std::fs::File;
+use std::io::Read;
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::os::unix::prelude::PermissionsExt;
 
 use serde::{Serialize, Deserialize};
 
 use zip::ZipArchive;
 use tar::Archive as TarArchive;
+use rayon::prelude::*;
 // use sevenz_rust::SevenZReader as SevenzArchive;
 
 use xz::read::XzDecoder as XzReader;
 use bzip2::read::BzDecoder as Bz2Reader;
 use flate2::read::GzDecoder as GzReader;
+use zstd::stream::read::Decoder as ZstdReader;
 
 /// Get 7z binary if some is available
 fn get7z() -> anyhow::Result<String> {
@@ -53,12 +58,177 @@ impl Size {
     }
 }
 
+/// Archive format detected by the file's magic bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ArchiveFormat {
+    Zip,
+    SevenZ,
+    Tar,
+    TarXz,
+    TarGz,
+    TarBz2,
+
+    /// Single zstd compressed file
+    Zstd
+}
+
+/// Detect archive format by reading its first bytes
+///
+/// Compressed streams (xz, gz, bz2) are expected to contain tar archives
+pub fn detect_format(path: impl AsRef<Path>) -> anyhow::Result<Option<ArchiveFormat>> {
+    let mut header = Vec::with_capacity(262);
+
+    // Tar archives store "ustar" magic at the 257th byte
+    File::open(path)?.take(262).read_to_end(&mut header)?;
+
+    if header.starts_with(b"PK\x03\x04") {
+        Ok(Some(ArchiveFormat::Zip))
+    }
+
+    else if header.starts_with(&[0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C]) {
+        Ok(Some(ArchiveFormat::SevenZ))
+    }
+
+    else if header.starts_with(&[0xFD, 0x37, 0x7A, 0x58, 0x5A, 0x00]) {
+        Ok(Some(ArchiveFormat::TarXz))
+    }
+
+    else if header.starts_with(&[0x1F, 0x8B]) {
+        Ok(Some(ArchiveFormat::TarGz))
+    }
+
+    else if header.starts_with(b"BZh") {
+        Ok(Some(ArchiveFormat::TarBz2))
+    }
+
+    else if header.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+        Ok(Some(ArchiveFormat::Zstd))
+    }
+
+    else if header.len() >= 262 && &header[257..262] == b"ustar" {
+        Ok(Some(ArchiveFormat::Tar))
+    }
+
+    else {
+        Ok(None)
+    }
+}
+
+/// Get name of the file stored in zstd archive
+///
+/// - `patch.dll.zst` -> `patch.dll`
+/// - `patch.dll` -> `patch.dll`
+/// - `.zst` -> error
+fn zstd_file_name(path: &Path) -> anyhow::Result<String> {
+    let name = path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let name = match name.strip_suffix(".zst") {
+        Some(name) => name.to_string(),
+        None => name
+    };
+
+    if name.is_empty() {
+        anyhow::bail!("Failed to get name of the file stored in zstd archive: {:?}", path);
+    }
+
+    Ok(name)
+}
+
+/// Get decompressed size of the zstd archive
+///
+/// Size is read from the frame header if it's stored there.
+/// Otherwise the archive is decompressed to count it
+fn zstd_content_size(path: &Path) -> anyhow::Result<u64> {
+    // Frame header is at most 18 bytes long
+    let mut header = Vec::with_capacity(18);
+
+    File::open(path)?.take(18).read_to_end(&mut header)?;
+
+    match zstd::zstd_safe::get_frame_content_size(&header) {
+        Ok(Some(size)) => Ok(size),
+
+        // Content size is not stored in the header
+        _ => Ok(std::io::copy(&mut ZstdReader::new(File::open(path)?)?, &mut std::io::sink())?)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Entry {
     pub name: String,
     pub size: Size
 }
 
+/// Resolve archive entry path within the base folder
+///
+/// Return `None` if the entry path is absolute or leaves the base folder
+/// using `..` components, which could be used to overwrite any file in the system
+///
+/// - `(/game, data/file.txt)` -> `Some(/game/data/file.txt)`
+/// - `(/game, ../file.txt)` -> `None`
+pub fn sanitize_entry_path(base: &Path, entry_path: &Path) -> Option<PathBuf> {
+    let mut path = base.to_path_buf();
+    let mut depth = 0;
+
+    for component in entry_path.components() {
+        match component {
+            Component::Normal(part) => {
+                path.push(part);
+
+                depth += 1;
+            }
+
+            Component::ParentDir => {
+                if depth == 0 {
+                    return None;
+                }
+
+                path.pop();
+
+                depth -= 1;
+            }
+
+            Component::CurDir => (),
+
+            Component::RootDir |
+            Component::Prefix(_) => return None
+        }
+    }
+
+    Some(path)
+}
+
+/// Unpack tar archive entries accepted by the filter
+///
+/// Return total size of unpacked entries
+fn unpack_tar_filtered<R: Read>(tar: &mut TarArchive<R>, folder: &Path, filter: impl Fn(&Entry) -> bool) -> anyhow::Result<u64> {
+    let mut total = 0;
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+
+        let info = Entry {
+            name: entry.path()?.to_string_lossy().to_string(),
+            size: Size::Compressed(entry.size())
+        };
+
+        if filter(&info) {
+            if sanitize_entry_path(folder, Path::new(&info.name)).is_none() {
+                tracing::warn!("Skipping unsafe archive entry: {}", info.name);
+
+                continue;
+            }
+
+            entry.unpack_in(folder)?;
+
+            total += info.size.get_size();
+        }
+    }
+
+    Ok(total)
+}
+
 pub enum Archive {
     Zip(PathBuf, ZipArchive<File>),
     Tar(PathBuf, TarArchive<File>),
@@ -66,7 +236,8 @@ pub enum Archive {
     TarGz(PathBuf, TarArchive<GzReader<File>>),
     TarBz2(PathBuf, TarArchive<Bz2Reader<File>>),
     SevenZ(PathBuf/*, SevenzArchive<File>*/),
-    ZipMultipart(PathBuf)
+    ZipMultipart(PathBuf),
+    Zstd(PathBuf)
 }
 
 impl Archive {
@@ -76,36 +247,114 @@ impl Archive {
 
         let path_str = path.to_string_lossy();
 
-        if &path_str[path_str.len() - 4..] == ".zip" {
+        if path_str.ends_with(".zip") {
             Ok(Archive::Zip(path, ZipArchive::new(file)?))
         }
 
-        else if &path_str[path_str.len() - 7..] == ".tar.xz" {
+        else if path_str.ends_with(".tar.xz") {
             Ok(Archive::TarXz(path, TarArchive::new(XzReader::new(file))))
         }
 
-        else if &path_str[path_str.len() - 7..] == ".tar.gz" {
+        else if path_str.ends_with(".tar.gz") {
             Ok(Archive::TarGz(path, TarArchive::new(GzReader::new(file))))
         }
 
-        else if &path_str[path_str.len() - 8..] == ".tar.bz2" {
+        else if path_str.ends_with(".tar.bz2") {
             Ok(Archive::TarBz2(path, TarArchive::new(Bz2Reader::new(file))))
         }
 
-        else if &path_str[path_str.len() - 3..] == ".7z" {
+        else if path_str.ends_with(".7z") {
             Ok(Archive::SevenZ(path/*, SevenzArchive::open(path, &[])?*/))
         }
 
-        else if &path_str[path_str.len() - 4..] == ".tar" {
+        else if path_str.ends_with(".tar") {
             Ok(Archive::Tar(path, TarArchive::new(file)))
         }
 
-        else if &path_str[path_str.len() - 8..] == ".zip.001" || &path_str[path_str.len() - 7..] == ".7z.001" || &path_str[path_str.len() - 4..] == ".z01" {
+        else if path_str.ends_with(".zst") {
+            Ok(Archive::Zstd(path))
+        }
+
+        else if path_str.ends_with(".zip.001") || path_str.ends_with(".7z.001") || path_str.ends_with(".z01") {
             Ok(Archive::ZipMultipart(path))
         }
 
+        // Unknown extension - try to detect the format by the file's content
         else {
-            Err(anyhow::anyhow!("Archive format is not supported: {}", path.to_string_lossy()))
+            match detect_format(&path)? {
+                Some(format) => Self::open_as(path, format),
+                None => Err(anyhow::anyhow!("Archive format is not supported: {}", path.to_string_lossy()))
+            }
+        }
+    }
+
+    /// Open archive using its magic bytes to detect the format, ignoring the file's extension
+    ///
+    /// Multipart archives can't be detected this way and should be opened with `Archive::open`
+    pub fn open_auto<T: Into<PathBuf>>(path: T) -> anyhow::Result<Self> {
+        let path: PathBuf = path.into();
+
+        match detect_format(&path)? {
+            Some(format) => Self::open_as(path, format),
+            None => Err(anyhow::anyhow!("Failed to detect archive format: {}", path.to_string_lossy()))
+        }
+    }
+
+    fn open_as(path: PathBuf, format: ArchiveFormat) -> anyhow::Result<Self> {
+        let file = File::open(&path)?;
+
+        match format {
+            ArchiveFormat::Zip    => Ok(Archive::Zip(path, ZipArchive::new(file)?)),
+            ArchiveFormat::SevenZ => Ok(Archive::SevenZ(path)),
+            ArchiveFormat::Tar    => Ok(Archive::Tar(path, TarArchive::new(file))),
+            ArchiveFormat::TarXz  => Ok(Archive::TarXz(path, TarArchive::new(XzReader::new(file)))),
+            ArchiveFormat::TarGz  => Ok(Archive::TarGz(path, TarArchive::new(GzReader::new(file)))),
+            ArchiveFormat::TarBz2 => Ok(Archive::TarBz2(path, TarArchive::new(Bz2Reader::new(file)))),
+            ArchiveFormat::Zstd   => Ok(Archive::Zstd(path))
+        }
+    }
+
+    /// Get `(compressed, uncompressed)` size of the archive
+    ///
+    /// Zip sizes are read from the central directory and 7z sizes from
+    /// the archive listing, so no data is decompressed. Tar archives
+    /// store no totals and have to be read entirely. Compressed size of
+    /// tar and 7z archives is the size of the archive file itself.
+    ///
+    /// Tar archives may forbid you to extract them if you call this method
+    pub fn total_size(&mut self) -> anyhow::Result<(u64, u64)> {
+        match self {
+            Archive::Zip(_, zip) => {
+                let mut compressed = 0;
+                let mut uncompressed = 0;
+
+                for i in 0..zip.len() {
+                    let entry = zip.by_index_raw(i)?;
+
+                    compressed += entry.compressed_size();
+                    uncompressed += entry.size();
+                }
+
+                Ok((compressed, uncompressed))
+            }
+
+            Archive::Zstd(path) => Ok((path.metadata()?.len(), zstd_content_size(path)?)),
+
+            Archive::Tar(path, _) |
+            Archive::TarXz(path, _) |
+            Archive::TarGz(path, _) |
+            Archive::TarBz2(path, _) |
+            Archive::SevenZ(path) |
+            Archive::ZipMultipart(path) => {
+                let compressed = path.metadata()?.len();
+
+                let uncompressed = self.get_entries()?
+                    .iter()
+                    .map(|entry| entry.size.get_size())
+                    .sum();
+
+                Ok((compressed, uncompressed))
+            }
         }
     }
 
@@ -164,6 +413,16 @@ impl Archive {
                 }
             }
 
+            Archive::Zstd(path) => {
+                entries.push(Entry {
+                    name: zstd_file_name(path)?,
+                    size: Size::Both {
+                        compressed: path.metadata()?.len(),
+                        uncompressed: zstd_content_size(path)?
+                    }
+                });
+            }
+
             #[allow(unused_must_use)]
             Archive::SevenZ(path) |
             Archive::ZipMultipart(path) => {
@@ -244,7 +503,15 @@ impl Archive {
 
         match self {
             Archive::Zip(archive, zip) => {
-                if zip.extract(&folder).is_err() {
+                let unsafe_entry = zip.file_names()
+                    .any(|name| sanitize_entry_path(&folder, Path::new(name)).is_none());
+
+                // Extract entries one by one skipping unsafe ones
+                if unsafe_entry {
+                    self.extract_filtered(folder, |_| true)?;
+                }
+
+                else if zip.extract(&folder).is_err() {
                     Command::new("unzip")
                         .arg("-q")
                         .arg("-o")
@@ -256,25 +523,55 @@ impl Archive {
             }
 
             Archive::Tar(_, tar) => {
-                tar.unpack(folder)?;
+                std::fs::create_dir_all(&folder)?;
+
+                unpack_tar_filtered(tar, &folder, |_| true)?;
             }
 
             Archive::TarXz(_, tar) => {
-                tar.unpack(folder)?;
+                std::fs::create_dir_all(&folder)?;
+
+                unpack_tar_filtered(tar, &folder, |_| true)?;
             }
 
             Archive::TarGz(_, tar) => {
-                tar.unpack(folder)?;
+                std::fs::create_dir_all(&folder)?;
+
+                unpack_tar_filtered(tar, &folder, |_| true)?;
             }
 
             Archive::TarBz2(_, tar) => {
-                tar.unpack(folder)?;
+                std::fs::create_dir_all(&folder)?;
+
+                unpack_tar_filtered(tar, &folder, |_| true)?;
+            }
+
+            Archive::Zstd(archive) => {
+                std::fs::create_dir_all(&folder)?;
+
+                let mut decoder = ZstdReader::new(File::open(&archive)?)?;
+                let mut file = File::create(folder.join(zstd_file_name(archive)?))?;
+
+                std::io::copy(&mut decoder, &mut file)?;
             }
 
             Archive::SevenZ(archive) |
             Archive::ZipMultipart(archive) => {
                 // sevenz_rust::decompress_file(archive, folder.into())?;
 
+                let archive = archive.clone();
+
+                // Exclude unsafe entries from extraction
+                let excluded = self.get_entries()?
+                    .into_iter()
+                    .filter(|entry| sanitize_entry_path(&folder, Path::new(&entry.name)).is_none())
+                    .map(|entry| {
+                        tracing::warn!("Skipping unsafe archive entry: {}", entry.name);
+
+                        format!("-x!{}", entry.name)
+                    })
+                    .collect::<Vec<_>>();
+
                 // Workaround to allow 7z to overwrite files
                 // Somehow it manages to forbid itself to do this
                 Command::new("chmod")
@@ -289,6 +586,8 @@ impl Archive {
                     .arg(archive)
                     .arg(format!("-o{}", folder.to_string_lossy()))
                     .arg("-aoa")
+                    .arg("-spd")
+                    .args(excluded)
                     .output()?;
 
                 // Change permissions again
@@ -302,4 +601,363 @@ impl Archive {
 
         Ok(())
     }
+
+    /// Extract only archive entries accepted by the filter
+    ///
+    /// Return total size of extracted entries
+    #[tracing::instrument(level = "debug", skip(self, filter))]
+    pub fn extract_filtered<T: Into<PathBuf> + std::fmt::Debug>(&mut self, folder: T, filter: impl Fn(&Entry) -> bool) -> anyhow::Result<u64> {
+        tracing::trace!("Extracting archive");
+
+        let folder = folder.into();
+
+        std::fs::create_dir_all(&folder)?;
+
+        match self {
+            Archive::Zip(_, zip) => {
+                let mut total = 0;
+
+                for i in 0..zip.len() {
+                    let mut entry = zip.by_index(i)?;
+
+                    let info = Entry {
+                        name: entry.name().to_string(),
+                        size: Size::Both {
+                            compressed: entry.compressed_size(),
+                            uncompressed: entry.size()
+                        }
+                    };
+
+                    if !filter(&info) {
+                        continue;
+                    }
+
+                    let Some(path) = sanitize_entry_path(&folder, Path::new(&info.name)) else {
+                        tracing::warn!("Skipping unsafe archive entry: {}", info.name);
+
+                        continue;
+                    };
+
+                    if entry.is_dir() {
+                        std::fs::create_dir_all(&path)?;
+                    }
+
+                    else {
+                        if let Some(parent) = path.parent() {
+                            std::fs::create_dir_all(parent)?;
+                        }
+
+                        std::io::copy(&mut entry, &mut File::create(&path)?)?;
+                    }
+
+                    if let Some(mode) = entry.unix_mode() {
+                        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))?;
+                    }
+
+                    total += info.size.get_size();
+                }
+
+                Ok(total)
+            }
+
+            Archive::Tar(_, tar)    => unpack_tar_filtered(tar, &folder, filter),
+            Archive::TarXz(_, tar)  => unpack_tar_filtered(tar, &folder, filter),
+            Archive::TarGz(_, tar)  => unpack_tar_filtered(tar, &folder, filter),
+            Archive::TarBz2(_, tar) => unpack_tar_filtered(tar, &folder, filter),
+
+            Archive::Zstd(_) => {
+                let entries = self.get_entries()?;
+
+                if !filter(&entries[0]) {
+                    return Ok(0);
+                }
+
+                self.extract(folder)?;
+
+                Ok(entries[0].size.get_size())
+            }
+
+            Archive::SevenZ(archive) |
+            Archive::ZipMultipart(archive) => {
+                let archive = archive.clone();
+
+                let entries = self.get_entries()?
+                    .into_iter()
+                    .filter(|entry| filter(entry))
+                    .filter(|entry| {
+                        if sanitize_entry_path(&folder, Path::new(&entry.name)).is_some() {
+                            true
+                        } else {
+                            tracing::warn!("Skipping unsafe archive entry: {}", entry.name);
+
+                            false
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                if entries.is_empty() {
+                    return Ok(0);
+                }
+
+                // Workaround to allow 7z to overwrite files
+                Command::new("chmod")
+                    .arg("-R")
+                    .arg("755")
+                    .arg(&folder)
+                    .output()?;
+
+                // Extract listed entries only. -spd disables wildcards matching
+                Command::new(get7z()?)
+                    .arg("x")
+                    .arg(&archive)
+                    .arg(format!("-o{}", folder.to_string_lossy()))
+                    .arg("-aoa")
+                    .arg("-spd")
+                    .arg("--")
+                    .args(entries.iter().map(|entry| &entry.name))
+                    .output()?;
+
+                Command::new("chmod")
+                    .arg("-R")
+                    .arg("755")
+                    .arg(&folder)
+                    .output()?;
+
+                Ok(entries.iter().map(|entry| entry.size.get_size()).sum())
+            }
+        }
+    }
+
+    /// Extract zip archive using multiple threads
+    ///
+    /// Every thread opens its own copy of the archive and decompresses
+    /// different entries. `progress` is called with `(extracted bytes, total bytes)`
+    /// from the worker threads. Other formats are extracted using `Archive::extract`
+    #[tracing::instrument(level = "debug", skip(self, progress))]
+    pub fn extract_parallel<T: Into<PathBuf> + std::fmt::Debug>(&mut self, folder: T, thread_count: usize, progress: impl Fn(u64, u64) + Send + Sync) -> anyhow::Result<()> {
+        let folder = folder.into();
+
+        let Archive::Zip(archive, zip) = self else {
+            return self.extract(folder);
+        };
+
+        tracing::trace!("Extracting archive using {thread_count} threads");
+
+        let total = (0..zip.len())
+            .map(|i| zip.by_index_raw(i).map(|entry| entry.size()))
+            .sum::<Result<u64, _>>()?;
+
+        let extracted = AtomicU64::new(0);
+
+        std::fs::create_dir_all(&folder)?;
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(thread_count)
+            .build()?;
+
+        pool.install(|| {
+            (0..zip.len()).into_par_iter().try_for_each_init(
+                || File::open(&archive).map_err(anyhow::Error::from).and_then(|file| Ok(ZipArchive::new(file)?)),
+                |zip, i| -> anyhow::Result<()> {
+                    let zip = match zip {
+                        Ok(zip) => zip,
+                        Err(err) => anyhow::bail!("Failed to open archive: {err}")
+                    };
+
+                    let mut entry = zip.by_index(i)?;
+
+                    let Some(path) = sanitize_entry_path(&folder, Path::new(entry.name())) else {
+                        tracing::warn!("Skipping unsafe archive entry: {}", entry.name());
+
+                        return Ok(());
+                    };
+
+                    if entry.is_dir() {
+                        std::fs::create_dir_all(&path)?;
+                    }
+
+                    else {
+                        if let Some(parent) = path.parent() {
+                            std::fs::create_dir_all(parent)?;
+                        }
+
+                        std::io::copy(&mut entry, &mut File::create(&path)?)?;
+                    }
+
+                    if let Some(mode) = entry.unix_mode() {
+                        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))?;
+                    }
+
+                    let size = extracted.fetch_add(entry.size(), Ordering::Relaxed) + entry.size();
+
+                    progress(size, total);
+
+                    Ok(())
+                }
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format() -> anyhow::Result<()> {
+        let folder = std::env::temp_dir().join(".anime-game-core-test-detect-format");
+
+        std::fs::create_dir_all(&folder)?;
+
+        let formats: [(&str, &[u8], Option<ArchiveFormat>); 7] = [
+            ("zip.bak", b"PK\x03\x04\x14\x00", Some(ArchiveFormat::Zip)),
+            ("7z", &[0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C, 0x00, 0x04], Some(ArchiveFormat::SevenZ)),
+            ("xz", &[0xFD, 0x37, 0x7A, 0x58, 0x5A, 0x00], Some(ArchiveFormat::TarXz)),
+            ("gz", &[0x1F, 0x8B, 0x08], Some(ArchiveFormat::TarGz)),
+            ("bz2", b"BZh91AY", Some(ArchiveFormat::TarBz2)),
+            ("zst", &[0x28, 0xB5, 0x2F, 0xFD, 0x04], Some(ArchiveFormat::Zstd)),
+            ("a", b"Hello", None)
+        ];
+
+        for (name, header, format) in formats {
+            let path = folder.join(name);
+
+            std::fs::write(&path, header)?;
+
+            assert_eq!(detect_format(&path)?, format);
+        }
+
+        std::fs::remove_dir_all(folder)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_zstd() -> anyhow::Result<()> {
+        let folder = std::env::temp_dir().join(".anime-game-core-test-zstd");
+
+        std::fs::create_dir_all(&folder)?;
+
+        let path = folder.join("example.txt.zst");
+
+        std::fs::write(&path, zstd::encode_all(&b"Hello, World!"[..], 0)?)?;
+
+        let mut archive = Archive::open(&path)?;
+
+        assert_eq!(archive.get_entries()?[0], Entry {
+            name: String::from("example.txt"),
+            size: Size::Both {
+                compressed: path.metadata()?.len(),
+                uncompressed: 13
+            }
+        });
+
+        archive.extract(folder.join("output"))?;
+
+        assert_eq!(std::fs::read(folder.join("output/example.txt"))?, b"Hello, World!");
+
+        assert!(zstd_file_name(Path::new("/tmp/.zst")).is_err());
+
+        std::fs::remove_dir_all(folder)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_filtered() -> anyhow::Result<()> {
+        let folder = std::env::temp_dir().join(".anime-game-core-test-extract-filtered");
+
+        std::fs::create_dir_all(&folder)?;
+
+        let path = folder.join("example.tar");
+
+        let mut builder = tar::Builder::new(File::create(&path)?);
+
+        for (name, data) in [("a.txt", "Hello"), ("b.txt", "World")] {
+            let mut header = tar::Header::new_gnu();
+
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+
+            builder.append_data(&mut header, name, data.as_bytes())?;
+        }
+
+        builder.finish()?;
+
+        drop(builder);
+
+        let total = Archive::open(&path)?
+            .extract_filtered(folder.join("output"), |entry| entry.name == "b.txt")?;
+
+        assert_eq!(total, 5);
+        assert!(!folder.join("output/a.txt").exists());
+        assert_eq!(std::fs::read(folder.join("output/b.txt"))?, b"World");
+
+        std::fs::remove_dir_all(folder)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sanitize_entry_path() {
+        let base = Path::new("/game");
+
+        assert_eq!(sanitize_entry_path(base, Path::new("data/file.txt")), Some(PathBuf::from("/game/data/file.txt")));
+        assert_eq!(sanitize_entry_path(base, Path::new("./data/../file.txt")), Some(PathBuf::from("/game/file.txt")));
+
+        assert_eq!(sanitize_entry_path(base, Path::new("../file.txt")), None);
+        assert_eq!(sanitize_entry_path(base, Path::new("data/../../file.txt")), None);
+        assert_eq!(sanitize_entry_path(base, Path::new("/etc/passwd")), None);
+    }
+
+    #[test]
+    fn test_extract_parallel() -> anyhow::Result<()> {
+        use std::io::Write;
+
+        let folder = std::env::temp_dir().join(".anime-game-core-test-extract-parallel");
+
+        std::fs::create_dir_all(&folder)?;
+
+        let path = folder.join("example.zip");
+
+        let mut writer = zip::ZipWriter::new(File::create(&path)?);
+
+        for i in 0..16 {
+            writer.start_file(format!("data/{i}.txt"), zip::write::SimpleFileOptions::default())?;
+            writer.write_all(format!("File {i}").as_bytes())?;
+        }
+
+        writer.finish()?;
+
+        Archive::open(&path)?.extract_parallel(folder.join("output"), 4, |_, _| {})?;
+
+        for i in 0..16 {
+            assert_eq!(std::fs::read_to_string(folder.join(format!("output/data/{i}.txt")))?, format!("File {i}"));
+        }
+
+        std::fs::remove_dir_all(folder)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_total_size() -> anyhow::Result<()> {
+        let folder = std::env::temp_dir().join(".anime-game-core-test-total-size");
+
+        std::fs::create_dir_all(&folder)?;
+
+        let path = folder.join("example.txt.zst");
+
+        std::fs::write(&path, zstd::encode_all(&[0; 1024][..], 0)?)?;
+
+        let (compressed, uncompressed) = Archive::open(&path)?.total_size()?;
+
+        assert_eq!(compressed, path.metadata()?.len());
+        assert_eq!(uncompressed, 1024);
+
+        std::fs::remove_dir_all(folder)?;
+
+        Ok(())
+    }
 }
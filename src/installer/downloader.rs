@@ -1,12 +1,15 @@
 use std::io::{Write, Seek};
 use std::path::PathBuf;
 use std::fs::File;
+use std::sync::{Arc, OnceLock};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use serde::{Serialize, Deserialize};
 use thiserror::Error;
 
 use super::free_space;
 use crate::prettify_bytes::prettify_bytes;
+use crate::hash::{self, HashAlgorithm};
 
 /// Default amount of bytes `Downloader::download` method will send to `downloader` function
 pub const DEFAULT_CHUNK_SIZE: usize = 128 * 1024; // 128 KB
@@ -39,6 +42,25 @@ pub enum DownloadingError {
     #[error("Failed to read metadata of the output file {0:?}: {1}")]
     OutputFileMetadataError(PathBuf, String),
 
+    /// Server responded with unexpected status code
+    ///
+    /// `(status code)`
+    #[error("Unexpected response status code: {0}")]
+    UnexpectedStatusCode(i32),
+
+    /// Specified proxy is invalid or not supported
+    ///
+    /// `(proxy, error message)`
+    #[error("Invalid proxy {0}: {1}")]
+    InvalidProxy(String, String),
+
+    /// Hash of the downloaded file doesn't match the expected one
+    #[error("Downloaded file hash mismatch: expected {expected}, got {got}")]
+    HashMismatch {
+        expected: String,
+        got: String
+    },
+
     /// minreq error
     #[error("minreq error: {0}")]
     Minreq(String)
@@ -53,7 +75,9 @@ impl From<minreq::Error> for DownloadingError {
 #[derive(Debug)]
 pub struct Downloader {
     uri: String,
-    length: Option<u64>,
+
+    /// Content length requested on the first `Downloader::length` call
+    length: OnceLock<Option<u64>>,
 
     /// Amount of bytes `Downloader::download` method will send to `downloader` function
     pub chunk_size: usize,
@@ -63,30 +87,63 @@ pub struct Downloader {
     pub continue_downloading: bool,
 
     /// Perform free space verifications before downloading file
-    pub check_free_space: bool
+    pub check_free_space: bool,
+
+    /// Amount of parallel connections used to download the file
+    pub connections: usize,
+
+    /// Proxy used to send all the requests
+    proxy: Option<String>,
+
+    /// Additional headers sent with all the requests
+    headers: Vec<(String, String)>,
+
+    /// Mirrors used if the main uri is not available
+    fallback_urls: Vec<String>,
+
+    /// Hash the downloaded file is verified against
+    expected_hash: Option<(String, HashAlgorithm)>
 }
 
 impl Downloader {
+    /// Create new downloader
+    ///
+    /// No requests are sent here. Content length is requested
+    /// when it's needed for the first time using all the specified settings
     pub fn new<T: AsRef<str>>(uri: T) -> Result<Self, minreq::Error> {
         let uri = uri.as_ref();
 
-        let header = minreq::head(uri)
-            .with_timeout(*crate::REQUESTS_TIMEOUT)
-            .send()?;
-
-        let length = header.headers.get("content-length")
-            .map(|len| len.parse().expect("Requested site's content-length is not a number"));
+        #[cfg(any(test, feature = "local-uris"))]
+        if Self::is_local_uri(uri) {
+            std::fs::metadata(&uri[7..])
+                .map_err(minreq::Error::IoError)?;
+        }
 
         Ok(Self {
             uri: uri.to_owned(),
-            length,
+            length: OnceLock::new(),
 
             chunk_size: DEFAULT_CHUNK_SIZE,
             continue_downloading: true,
-            check_free_space: true
+            check_free_space: true,
+            connections: 1,
+            proxy: None,
+            headers: Vec::new(),
+            fallback_urls: Vec::new(),
+            expected_hash: None
         })
     }
 
+    #[cfg(any(test, feature = "local-uris"))]
+    #[inline]
+    /// Check if the uri points to a local file
+    ///
+    /// - `file:///tmp/example.zip` -> `true`
+    /// - `https://example.com/example.zip` -> `false`
+    pub fn is_local_uri(uri: &str) -> bool {
+        uri.starts_with("file://")
+    }
+
     #[inline]
     /// Specify downloading chunk size
     pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
@@ -112,9 +169,77 @@ impl Downloader {
     }
 
     #[inline]
+    /// Specify amount of parallel connections used to download the file
+    ///
+    /// The file will be split into equal parts downloaded in separate threads.
+    /// Default is 1, and 0 is treated as 1
+    pub fn with_connections(mut self, connections: usize) -> Self {
+        self.connections = connections.max(1);
+
+        self
+    }
+
+    /// Send all the requests through specified proxy
+    ///
+    /// Proxy should be specified as `http://[user:password@]host:port` or `host:port`.
+    ///
+    /// Only HTTP proxies are supported because minreq can't tunnel requests
+    /// through SOCKS, so `socks5://host:port` and other schemes return
+    /// `DownloadingError::InvalidProxy`. SOCKS proxy can still be used through
+    /// a local HTTP-to-SOCKS bridge.
+    pub fn with_proxy(mut self, proxy: &str) -> Result<Self, DownloadingError> {
+        if proxy.contains("://") && !proxy.starts_with("http://") {
+            return Err(DownloadingError::InvalidProxy(proxy.to_owned(), String::from("only http proxies are supported, SOCKS and other proxy types are not implemented by minreq")));
+        }
+
+        if let Err(err) = minreq::Proxy::new(proxy) {
+            return Err(DownloadingError::InvalidProxy(proxy.to_owned(), err.to_string()));
+        }
+
+        self.proxy = Some(proxy.to_owned());
+        self.length = OnceLock::new();
+
+        Ok(self)
+    }
+
+    #[inline]
+    /// Add header to all the requests made by this downloader
+    pub fn with_header(mut self, key: &str, value: &str) -> Self {
+        self.headers.push((key.to_owned(), value.to_owned()));
+        self.length = OnceLock::new();
+
+        self
+    }
+
+    #[inline]
+    /// Specify mirrors used if the main uri is not available
+    ///
+    /// Next uri is used on connection errors and HTTP 5xx responses.
+    /// If all of them have failed, the last error is returned
+    pub fn with_fallback_urls(mut self, urls: Vec<String>) -> Self {
+        self.fallback_urls = urls;
+        self.length = OnceLock::new();
+
+        self
+    }
+
+    #[inline]
+    /// Verify hash of the downloaded file
+    ///
+    /// `DownloadingError::HashMismatch` is returned if it doesn't match
+    pub fn with_expected_hash(mut self, hash: &str, algorithm: HashAlgorithm) -> Self {
+        self.expected_hash = Some((hash.to_owned(), algorithm));
+
+        self
+    }
+
     /// Get content length
+    ///
+    /// It's requested only once with all the specified headers and proxy
+    /// from the first available uri, including the fallback ones.
+    /// `None` if none of them reported it
     pub fn length(&self) -> Option<u64> {
-        self.length
+        *self.length.get_or_init(|| self.request_length())
     }
 
     /// Get name of downloading file from uri
@@ -134,6 +259,63 @@ impl Downloader {
     pub fn download(&mut self, path: impl Into<PathBuf>, progress: impl Fn(u64, u64) + Send + 'static) -> Result<(), DownloadingError> {
         let path = path.into();
 
+        let uri = self.uri.clone();
+        let mut fallback_urls = self.fallback_urls.clone().into_iter();
+
+        let result = loop {
+            let result = self.download_from_uri(path.clone(), &progress);
+
+            match result {
+                Err(DownloadingError::Minreq(_)) |
+                Err(DownloadingError::UnexpectedStatusCode(500..=599)) => {
+                    let Some(fallback) = fallback_urls.next() else {
+                        break result;
+                    };
+
+                    tracing::warn!("Failed to download file from {}: {}. Trying {fallback}", self.uri, result.unwrap_err());
+
+                    self.uri = fallback;
+                }
+
+                _ => break result
+            }
+        };
+
+        self.uri = uri;
+
+        result?;
+
+        if let Some((expected, algorithm)) = &self.expected_hash {
+            tracing::debug!("Verifying downloaded file hash");
+
+            let got = match hash::hash_file(&path, *algorithm) {
+                Ok(hash) => hash,
+                Err(err) => return Err(DownloadingError::OutputFileError(path, err.to_string()))
+            };
+
+            if !got.eq_ignore_ascii_case(expected) {
+                return Err(DownloadingError::HashMismatch {
+                    expected: expected.to_owned(),
+                    got
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn download_from_uri(&mut self, path: PathBuf, progress: impl Fn(u64, u64)) -> Result<(), DownloadingError> {
+        #[cfg(any(test, feature = "local-uris"))]
+        if Self::is_local_uri(&self.uri) {
+            return self.copy_local(path, progress);
+        }
+
+        if let Some(length) = self.length() {
+            if self.connections > 1 && (self.connections as u64) <= length {
+                return self.download_parallel(path, length, progress);
+            }
+        }
+
         let mut downloaded = 0;
 
         // Open or create output file
@@ -215,7 +397,7 @@ impl Downloader {
             Ok(mut file) => {
                 let mut chunk = Vec::with_capacity(self.chunk_size);
 
-                let request = minreq::head(&self.uri)
+                let request = self.request(minreq::head(&self.uri))?
                     .with_header("range", format!("bytes={downloaded}-"))
                     .send()?;
 
@@ -228,26 +410,48 @@ impl Downloader {
                 if let Some(range) = request.headers.get("content-range") {
                     // Finish downloading if header says that we've already downloaded all the data
                     if range.contains("*/") {
-                        (progress)(self.length.unwrap_or(downloaded as u64), self.length.unwrap_or(downloaded as u64));
+                        (progress)(self.length().unwrap_or(downloaded as u64), self.length().unwrap_or(downloaded as u64));
 
                         return Ok(());
                     }
                 }
 
-                let request = minreq::get(&self.uri)
+                let request = self.request(minreq::get(&self.uri))?
                     .with_header("range", format!("bytes={downloaded}-"))
                     .send_lazy()?;
 
+                if request.status_code >= 500 {
+                    return Err(DownloadingError::UnexpectedStatusCode(request.status_code));
+                }
+
                 // HTTP 416 = provided range is overcame actual content length (means file is downloaded)
                 // I check this here because HEAD request can return 200 OK while GET - 416
                 //
                 // https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/416
                 if request.status_code == 416 {
-                    (progress)(self.length.unwrap_or(downloaded as u64), self.length.unwrap_or(downloaded as u64));
+                    (progress)(self.length().unwrap_or(downloaded as u64), self.length().unwrap_or(downloaded as u64));
 
                     return Ok(());
                 }
 
+                // HTTP 200 instead of 206 = server ignored the range header and sends the whole file,
+                // so we have to drop already downloaded data and write it again from the beginning
+                //
+                // https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/206
+                if request.status_code == 200 && downloaded > 0 {
+                    tracing::warn!("Server doesn't support ranged requests. Restarting downloading");
+
+                    if let Err(err) = file.set_len(0) {
+                        return Err(DownloadingError::OutputFileError(path, err.to_string()));
+                    }
+
+                    if let Err(err) = file.seek(std::io::SeekFrom::Start(0)) {
+                        return Err(DownloadingError::OutputFileError(path, err.to_string()));
+                    }
+
+                    downloaded = 0;
+                }
+
                 for byte in request {
                     let (byte, expected_len) = byte?;
 
@@ -262,7 +466,7 @@ impl Downloader {
 
                         downloaded += self.chunk_size;
 
-                        (progress)(downloaded as u64, self.length.unwrap_or(expected_len as u64));
+                        (progress)(downloaded as u64, self.length().unwrap_or(expected_len as u64));
                     }
                 }
 
@@ -282,4 +486,468 @@ impl Downloader {
             Err(err) => Err(DownloadingError::OutputFileError(path, err.to_string()))
         }
     }
+
+    /// Download the file using multiple connections
+    ///
+    /// Every connection downloads its own part of the file into `<path>.part<n>` file.
+    /// When all of them are finished these parts are merged into the output file
+    fn download_parallel(&mut self, path: PathBuf, length: u64, progress: impl Fn(u64, u64)) -> Result<(), DownloadingError> {
+        // Stop the process if the file is already downloaded
+        if self.continue_downloading {
+            if let Ok(metadata) = path.metadata() {
+                if metadata.len() == length {
+                    (progress)(length, length);
+
+                    return Ok(());
+                }
+            }
+        }
+
+        // Make sure that the server can send file parts.
+        // Otherwise download it using single connection.
+        // Lazy response is dropped without reading the body because servers
+        // which ignore the range header would send the whole file
+        let status_code = self.request(minreq::get(&self.uri))?
+            .with_header("range", "bytes=0-0")
+            .with_timeout(*crate::REQUESTS_TIMEOUT)
+            .send_lazy()?
+            .status_code;
+
+        if status_code != 206 {
+            tracing::warn!("Server doesn't support ranged requests. Using single connection");
+
+            let connections = self.connections;
+
+            self.connections = 1;
+
+            let result = self.download_from_uri(path, progress);
+
+            self.connections = connections;
+
+            return result;
+        }
+
+        if let Some(base_folder) = path.parent() {
+            if !base_folder.exists() {
+                if let Err(err) = std::fs::create_dir_all(base_folder) {
+                    return Err(DownloadingError::OutputFileError(path, err.to_string()));
+                }
+            }
+        }
+
+        // Parts and merged file are stored together so we need twice as much space
+        if self.check_free_space {
+            tracing::debug!("Checking free space availability");
+
+            match free_space::available(&path) {
+                Some(space) => {
+                    if space < length * 2 {
+                        return Err(DownloadingError::NoSpaceAvailable(path, length * 2, space));
+                    }
+                }
+
+                None => return Err(DownloadingError::PathNotMounted(path))
+            }
+        }
+
+        let connections = self.connections as u64;
+        let part_size = length / connections;
+
+        let downloaded = Arc::new(AtomicU64::new(0));
+
+        let mut parts = Vec::with_capacity(self.connections);
+        let mut handles = Vec::with_capacity(self.connections);
+
+        for i in 0..connections {
+            let start = i * part_size;
+
+            let end = if i == connections - 1 {
+                length - 1
+            } else {
+                start + part_size - 1
+            };
+
+            let mut part_path = path.clone().into_os_string();
+
+            part_path.push(format!(".part{i}"));
+
+            let part_path = PathBuf::from(part_path);
+
+            let request = self.request(minreq::get(&self.uri))?;
+            let chunk_size = self.chunk_size;
+            let continue_downloading = self.continue_downloading;
+            let downloaded = downloaded.clone();
+
+            parts.push(part_path.clone());
+
+            handles.push(std::thread::spawn(move || {
+                Self::download_part(request, part_path, (start, end), chunk_size, continue_downloading, downloaded)
+            }));
+        }
+
+        // Report downloading progress until all the parts are finished
+        loop {
+            let finished = handles.iter().all(|handle| handle.is_finished());
+
+            (progress)(downloaded.load(Ordering::Relaxed), length);
+
+            if finished {
+                break;
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+
+        for handle in handles {
+            match handle.join() {
+                Ok(result) => result?,
+                Err(_) => return Err(DownloadingError::OutputFileError(path, String::from("Downloading thread panicked")))
+            }
+        }
+
+        tracing::debug!("Merging downloaded parts");
+
+        let mut merged_path = path.clone().into_os_string();
+
+        merged_path.push(".merged");
+
+        let merged_path = PathBuf::from(merged_path);
+
+        let mut merged = match File::create(&merged_path) {
+            Ok(file) => file,
+            Err(err) => return Err(DownloadingError::OutputFileError(merged_path, err.to_string()))
+        };
+
+        for part_path in &parts {
+            let result = File::open(part_path)
+                .and_then(|mut part| std::io::copy(&mut part, &mut merged));
+
+            if let Err(err) = result {
+                return Err(DownloadingError::OutputFileError(merged_path, err.to_string()));
+            }
+        }
+
+        drop(merged);
+
+        if let Err(err) = std::fs::rename(&merged_path, &path) {
+            return Err(DownloadingError::OutputFileError(path, err.to_string()));
+        }
+
+        for part_path in parts {
+            if let Err(err) = std::fs::remove_file(&part_path) {
+                tracing::warn!("Failed to remove downloaded part {:?}: {err}", part_path);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Download `(start, end)` bytes range of the file using given GET request. Both bounds are inclusive
+    fn download_part(request: minreq::Request, path: PathBuf, range: (u64, u64), chunk_size: usize, continue_downloading: bool, downloaded: Arc<AtomicU64>) -> Result<(), DownloadingError> {
+        let (start, end) = range;
+        let size = end - start + 1;
+
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(!continue_downloading)
+            .open(&path);
+
+        let mut file = match file {
+            Ok(file) => file,
+            Err(err) => return Err(DownloadingError::OutputFileError(path, err.to_string()))
+        };
+
+        let mut offset = match file.metadata() {
+            Ok(metadata) => metadata.len(),
+            Err(err) => return Err(DownloadingError::OutputFileMetadataError(path, err.to_string()))
+        };
+
+        // Part is larger than it should be - download it again
+        if offset > size {
+            if let Err(err) = file.set_len(0) {
+                return Err(DownloadingError::OutputFileError(path, err.to_string()));
+            }
+
+            offset = 0;
+        }
+
+        if let Err(err) = file.seek(std::io::SeekFrom::Start(offset)) {
+            return Err(DownloadingError::OutputFileError(path, err.to_string()));
+        }
+
+        downloaded.fetch_add(offset, Ordering::Relaxed);
+
+        if offset == size {
+            return Ok(());
+        }
+
+        let request = request
+            .with_header("range", format!("bytes={}-{end}", start + offset))
+            .send_lazy()?;
+
+        if request.status_code != 206 {
+            return Err(DownloadingError::UnexpectedStatusCode(request.status_code));
+        }
+
+        let mut chunk = Vec::with_capacity(chunk_size);
+
+        for byte in request {
+            let (byte, _) = byte?;
+
+            chunk.push(byte);
+
+            if chunk.len() == chunk_size {
+                if let Err(err) = file.write_all(&chunk) {
+                    return Err(DownloadingError::OutputFileError(path, err.to_string()));
+                }
+
+                downloaded.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+
+                chunk.clear();
+            }
+        }
+
+        if !chunk.is_empty() {
+            if let Err(err) = file.write_all(&chunk) {
+                return Err(DownloadingError::OutputFileError(path, err.to_string()));
+            }
+
+            downloaded.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+        }
+
+        Ok(())
+    }
+
+    /// Request content length from the first available uri
+    ///
+    /// Connection errors and HTTP 5xx responses are skipped
+    /// so the error page's content length is not used
+    fn request_length(&self) -> Option<u64> {
+        for uri in std::iter::once(&self.uri).chain(&self.fallback_urls) {
+            #[cfg(any(test, feature = "local-uris"))]
+            if Self::is_local_uri(uri) {
+                match std::fs::metadata(&uri[7..]) {
+                    Ok(metadata) => return Some(metadata.len()),
+                    Err(err) => {
+                        tracing::warn!("Failed to get length of the local file {uri}: {err}");
+
+                        continue;
+                    }
+                }
+            }
+
+            let header = self.request(minreq::head(uri))
+                .and_then(|request| {
+                    request.with_timeout(*crate::REQUESTS_TIMEOUT)
+                        .send()
+                        .map_err(DownloadingError::from)
+                });
+
+            match header {
+                Ok(header) if header.status_code >= 500 => {
+                    tracing::warn!("Failed to request content length of {uri}: status code {}", header.status_code);
+                }
+
+                Ok(header) => return header.headers.get("content-length")
+                    .and_then(|length| length.parse().ok()),
+
+                Err(err) => {
+                    tracing::warn!("Failed to request content length of {uri}: {err}");
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Apply downloader's settings to the request
+    fn request(&self, request: minreq::Request) -> Result<minreq::Request, DownloadingError> {
+        let mut request = crate::request(request);
+
+        if !self.headers.is_empty() {
+            request = request.with_headers(self.headers.clone());
+        }
+
+        if let Some(proxy) = &self.proxy {
+            request = request.with_proxy(minreq::Proxy::new(proxy)?);
+        }
+
+        Ok(request)
+    }
+
+    #[cfg(any(test, feature = "local-uris"))]
+    /// Copy local file instead of downloading it
+    fn copy_local(&self, path: PathBuf, progress: impl Fn(u64, u64)) -> Result<(), DownloadingError> {
+        tracing::debug!("Copying local file");
+
+        if let Some(base_folder) = path.parent() {
+            if !base_folder.exists() {
+                if let Err(err) = std::fs::create_dir_all(base_folder) {
+                    return Err(DownloadingError::OutputFileError(path, err.to_string()));
+                }
+            }
+        }
+
+        if self.check_free_space {
+            match free_space::available(&path) {
+                Some(space) => {
+                    if let Some(required) = self.length() {
+                        if space < required {
+                            return Err(DownloadingError::NoSpaceAvailable(path, required, space));
+                        }
+                    }
+                }
+
+                None => return Err(DownloadingError::PathNotMounted(path))
+            }
+        }
+
+        match std::fs::copy(&self.uri[7..], &path) {
+            Ok(copied) => {
+                (progress)(copied, copied);
+
+                Ok(())
+            }
+
+            Err(err) => Err(DownloadingError::OutputFileError(path, err.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_uri() {
+        assert!(Downloader::is_local_uri("file:///tmp/example.zip"));
+        assert!(!Downloader::is_local_uri("https://example.com/example.zip"));
+    }
+
+    #[test]
+    fn test_local_download() -> Result<(), DownloadingError> {
+        let folder = std::env::temp_dir().join(".anime-game-core-test-local-download");
+
+        let source = folder.join("source.bin");
+        let output = folder.join("output/example.bin");
+
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(&source, b"Hello, World!").unwrap();
+
+        let mut downloader = Downloader::new(format!("file://{}", source.to_string_lossy()))?;
+
+        assert_eq!(downloader.length(), Some(13));
+        assert_eq!(downloader.get_filename(), "source.bin");
+
+        downloader.download(&output, |_, _| {})?;
+
+        assert_eq!(std::fs::read(&output).unwrap(), b"Hello, World!");
+
+        let mut downloader = Downloader::new(format!("file://{}", source.to_string_lossy()))?
+            .with_expected_hash("65a8e27d8879283831b664bd8b7f0ad4", HashAlgorithm::Md5);
+
+        downloader.download(&output, |_, _| {})?;
+
+        let mut downloader = Downloader::new(format!("file://{}", source.to_string_lossy()))?
+            .with_expected_hash("00000000000000000000000000000000", HashAlgorithm::Md5);
+
+        assert!(matches!(downloader.download(&output, |_, _| {}), Err(DownloadingError::HashMismatch { .. })));
+
+        std::fs::remove_dir_all(folder).unwrap();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_proxy() -> Result<(), DownloadingError> {
+        let downloader = Downloader::new(format!("file://{}", file!()))?;
+
+        assert!(matches!(downloader.with_proxy("socks5://127.0.0.1:1080"), Err(DownloadingError::InvalidProxy(_, _))));
+
+        let downloader = Downloader::new(format!("file://{}", file!()))?
+            .with_proxy("http://127.0.0.1:8080")?;
+
+        assert_eq!(downloader.proxy.as_deref(), Some("http://127.0.0.1:8080"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dead_primary() -> Result<(), DownloadingError> {
+        let folder = std::env::temp_dir().join(".anime-game-core-test-dead-primary");
+
+        let source = folder.join("source.bin");
+        let output = folder.join("output.bin");
+
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(&source, b"Hello, World!").unwrap();
+
+        // Nothing listens on the port 1 so the connection is refused
+        let mut downloader = Downloader::new("http://127.0.0.1:1/source.bin")?
+            .with_fallback_urls(vec![format!("file://{}", source.to_string_lossy())]);
+
+        assert_eq!(downloader.length(), Some(13));
+
+        downloader.download(&output, |_, _| {})?;
+
+        assert_eq!(std::fs::read(&output).unwrap(), b"Hello, World!");
+
+        std::fs::remove_dir_all(folder).unwrap();
+
+        Ok(())
+    }
+
+    /// Respond to a single HTTP request and return its text
+    fn serve_once(response: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let uri = format!("http://{}/archive.zip", listener.local_addr().unwrap());
+
+        let handle = std::thread::spawn(move || {
+            use std::io::Read;
+
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buf).unwrap();
+
+                if read == 0 {
+                    break;
+                }
+
+                request.extend_from_slice(&buf[..read]);
+            }
+
+            stream.write_all(response.as_bytes()).unwrap();
+
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        (uri, handle)
+    }
+
+    #[test]
+    fn test_headers() -> Result<(), DownloadingError> {
+        let (uri, server) = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 13\r\nConnection: close\r\n\r\nHello, World!");
+
+        let downloader = Downloader::new(uri)?
+            .with_header("Authorization", "Bearer token")
+            .with_header("X-Custom-Token", "example");
+
+        assert_eq!(downloader.length(), Some(13));
+
+        // Server is closed so the length must be cached
+        assert_eq!(downloader.length(), Some(13));
+
+        let request = server.join().unwrap().to_lowercase();
+
+        assert!(request.starts_with("head /archive.zip"));
+        assert!(request.contains("authorization: bearer token\r\n"));
+        assert!(request.contains("x-custom-token: example\r\n"));
+
+        Ok(())
+    }
 }
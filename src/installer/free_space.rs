@@ -1,6 +1,7 @@
 use std::path::Path;
 
 use sysinfo::Disks;
+use nix::sys::statvfs::statvfs;
 
 // TODO: support for relative paths
 
@@ -33,6 +34,32 @@ pub fn available(path: impl AsRef<Path>) -> Option<u64> {
     None
 }
 
+/// Get available free disk space by specified path using `statvfs`
+///
+/// Unlike `available` this function asks the filesystem of the path directly
+/// so it works for any existing path, including relative ones
+pub fn available_bytes(path: impl AsRef<Path>) -> anyhow::Result<u64> {
+    let stat = statvfs(path.as_ref())?;
+
+    // Fields types differ between platforms (e.g. `c_ulong` is 32 bits wide on 32-bit systems)
+    // so we widen them to u64 before doing any math
+    #[allow(clippy::useless_conversion)]
+    let blocks = u64::from(stat.blocks_available());
+
+    #[allow(clippy::useless_conversion)]
+    let block_size = u64::from(stat.fragment_size());
+
+    match multiply_blocks(blocks, block_size) {
+        Some(bytes) => Ok(bytes),
+        None => anyhow::bail!("Available space overflows u64: {blocks} blocks of {block_size} bytes")
+    }
+}
+
+#[inline]
+fn multiply_blocks(blocks: u64, block_size: u64) -> Option<u64> {
+    blocks.checked_mul(block_size)
+}
+
 /// Check if two paths exist on the same disk
 pub fn is_same_disk(path1: impl AsRef<Path>, path2: impl AsRef<Path>) -> bool {
     let mut disks = Disks::new();
@@ -65,3 +92,28 @@ pub fn is_same_disk(path1: impl AsRef<Path>, path2: impl AsRef<Path>) -> bool {
 
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multiply_blocks() {
+        assert_eq!(multiply_blocks(0, 4096), Some(0));
+        assert_eq!(multiply_blocks(1024, 4096), Some(4 * 1024 * 1024));
+        assert_eq!(multiply_blocks(u64::MAX, 1), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_multiply_blocks_overflow() {
+        assert_eq!(multiply_blocks(u64::MAX, 2), None);
+        assert_eq!(multiply_blocks(u64::MAX, u64::MAX), None);
+        assert_eq!(multiply_blocks(u32::MAX as u64 + 1, u32::MAX as u64 + 1), None);
+    }
+
+    #[test]
+    fn test_available_bytes() {
+        assert!(available_bytes(std::env::temp_dir()).is_ok());
+        assert!(available_bytes("/this/path/does/not/exist").is_err());
+    }
+}
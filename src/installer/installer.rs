@@ -1,14 +1,26 @@
 use std::path::PathBuf;
 use std::os::unix::prelude::PermissionsExt;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+use crate::version::Version;
 
 use super::downloader::{Downloader, DownloadingError};
-use super::archives::Archive;
+use super::archives::{Archive, Size};
 use super::free_space;
+use super::lock::{LockFile, LockError};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Update {
+    /// Installed version is already latest and nothing needs to be installed
+    ///
+    /// `(installed version)`
+    AlreadyLatest(Version),
+
     CheckingFreeSpace(PathBuf),
 
     /// `(temp path)`
@@ -35,7 +47,10 @@ pub enum Update {
     UnpackingProgress(u64, u64),
 
     UnpackingFinished,
-    UnpackingError(String)
+    UnpackingError(String),
+
+    /// Installation folder is locked by another process
+    LockError(LockError)
 }
 
 impl From<DownloadingError> for Update {
@@ -45,6 +60,90 @@ impl From<DownloadingError> for Update {
     }
 }
 
+impl From<LockError> for Update {
+    #[inline]
+    fn from(err: LockError) -> Self {
+        Self::LockError(err)
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstallerError {
+    #[error("{0}")]
+    DownloadingError(#[from] DownloadingError),
+
+    #[error("{0}")]
+    LockError(#[from] LockError),
+
+    /// Failed to open or extract downloaded archive
+    ///
+    /// `(error message)`
+    #[error("Failed to unpack archive: {0}")]
+    UnpackingError(String)
+}
+
+/// Expected unpacked data size relative to the archive size
+const UNPACKED_SIZE_RATIO: f64 = 1.5;
+
+#[inline]
+/// Estimate unpacked data size of the archive
+fn unpacked_size(archive_size: u64) -> u64 {
+    (archive_size as f64 * UNPACKED_SIZE_RATIO).ceil() as u64
+}
+
+#[inline]
+/// Estimate free space needed to download remained archive part and unpack it on the same disk
+fn required_space(archive_size: u64, downloaded: u64) -> u64 {
+    archive_size.saturating_sub(downloaded) + unpacked_size(archive_size)
+}
+
+/// Result of the `Installer::dry_run` method
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DryRunReport {
+    /// Amount of free space needed to download remained archive part and unpack it
+    ///
+    /// Unpacked data size is taken from the archive entries if it's already
+    /// downloaded, and estimated from the archive size otherwise
+    pub required_bytes: u64,
+
+    /// Amount of files in the archive. `None` if the archive is not downloaded yet
+    pub file_count: Option<usize>,
+
+    /// Files which will be written by the archive extraction, relative
+    /// to the installation folder. `None` if the archive is not downloaded yet
+    pub files: Option<Vec<PathBuf>>
+}
+
+/// List of files created during archive extraction
+///
+/// Files are removed when the manifest is dropped, so it must be
+/// cleared if the extraction has succeeded
+#[derive(Debug, Default)]
+struct RollbackManifest(Vec<PathBuf>);
+
+impl Drop for RollbackManifest {
+    fn drop(&mut self) {
+        if !self.0.is_empty() {
+            tracing::warn!("Removing {} files created during failed archive extraction", self.0.len());
+        }
+
+        // Remove deeper paths first so folders are empty when we get to them
+        self.0.sort_by_key(|path| std::cmp::Reverse(path.components().count()));
+
+        for path in self.0.drain(..) {
+            let result = if path.is_dir() {
+                std::fs::remove_dir(&path)
+            } else {
+                std::fs::remove_file(&path)
+            };
+
+            if let Err(err) = result {
+                tracing::warn!("Failed to remove {:?}: {err}", path);
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Installer {
     pub downloader: Downloader,
@@ -56,7 +155,13 @@ pub struct Installer {
     pub check_free_space: bool,
 
     /// How `Downloader` should save the file before unpacking it
-    pub filename: Option<String>
+    pub filename: Option<String>,
+
+    /// Remove unpacked files if archive extraction has failed
+    pub rollback: bool,
+
+    /// Already held lock of the installation folder
+    pub lock: Option<Arc<LockFile>>
 }
 
 impl Installer {
@@ -68,7 +173,9 @@ impl Installer {
 
             temp_folder: std::env::temp_dir(),
             check_free_space: true,
-            filename: None
+            filename: None,
+            rollback: false,
+            lock: None
         })
     }
 
@@ -110,13 +217,102 @@ impl Installer {
         self
     }
 
-    /// Download archive from specified uri and unpack it
-    pub fn install(&mut self, unpack_to: impl Into<PathBuf>, updater: impl Fn(Update) + Clone + Send + 'static) {
-        tracing::trace!("Checking free space availability");
+    #[inline]
+    /// Specify whether installer should remove unpacked files if archive extraction has failed
+    ///
+    /// Only files which didn't exist before the extraction are removed
+    pub fn with_rollback(mut self, rollback: bool) -> Self {
+        self.rollback = rollback;
+
+        self
+    }
+
+    #[inline]
+    /// Use already held lock instead of locking the installation folder
+    ///
+    /// Locks are exclusive within the process, so this is needed
+    /// to install something into the folder which is already locked by the caller.
+    /// The folder is still locked if the lock is held for another one
+    pub fn with_lock(mut self, lock: Arc<LockFile>) -> Self {
+        self.lock = Some(lock);
+
+        self
+    }
+
+    /// Estimate installation without downloading or writing anything
+    ///
+    /// Only content length of the archive is requested. Files list can be read
+    /// only when the archive is already fully downloaded to the temp folder
+    pub fn dry_run(&self) -> anyhow::Result<DryRunReport> {
+        let temp_path = self.get_temp_path();
 
+        let downloaded = match temp_path.metadata() {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0
+        };
+
+        let Some(length) = self.downloader.length() else {
+            anyhow::bail!("Failed to get archive size");
+        };
+
+        // Read archive entries if it's already downloaded
+        if length == downloaded {
+            let entries = Archive::open(&temp_path)?.get_entries()?;
+
+            let unpacked_size = entries.iter()
+                .map(|entry| match entry.size {
+                    Size::Both { uncompressed, .. } => uncompressed,
+                    _ => entry.size.get_size()
+                })
+                .sum();
+
+            let files = entries.into_iter()
+                .filter(|entry| !entry.name.ends_with('/'))
+                .map(|entry| PathBuf::from(entry.name))
+                .collect::<Vec<_>>();
+
+            return Ok(DryRunReport {
+                required_bytes: unpacked_size,
+                file_count: Some(files.len()),
+                files: Some(files)
+            });
+        }
+
+        Ok(DryRunReport {
+            required_bytes: required_space(length, downloaded),
+            file_count: None,
+            files: None
+        })
+    }
+
+    /// Download archive from specified uri and unpack it
+    ///
+    /// All the errors are reported through the `updater` and returned
+    pub fn install(&mut self, unpack_to: impl Into<PathBuf>, updater: impl Fn(Update) + Clone + Send + 'static) -> Result<(), InstallerError> {
         let temp_path = self.get_temp_path();
         let unpack_to = unpack_to.into();
 
+        tracing::trace!("Locking installation folder");
+
+        // Prevent other processes from installing anything into the same folder.
+        // The lock is released when this variable is dropped
+        let _lock = match &self.lock {
+            Some(lock) if lock.locks(&unpack_to) => None,
+
+            _ => match LockFile::new(&unpack_to) {
+                Ok(lock) => Some(lock),
+                Err(err) => {
+                    tracing::error!("Failed to lock installation folder: {err}");
+
+                    (updater)(err.clone().into());
+
+                    return Err(err.into());
+                }
+            }
+        };
+
+        tracing::trace!("Checking free space availability");
+
         // Perform free space verifications if needed
         if self.check_free_space {
             // Check available free space for archive itself
@@ -131,57 +327,53 @@ impl Installer {
             let Some(space) = free_space::available(&temp_path) else {
                 tracing::error!("Path is not mounted: {:?}", temp_path);
 
-                (updater)(DownloadingError::PathNotMounted(temp_path).into());
+                (updater)(DownloadingError::PathNotMounted(temp_path.clone()).into());
 
-                return;
+                return Err(DownloadingError::PathNotMounted(temp_path).into());
             };
 
             if let Some(required) = self.downloader.length() {
                 // We can possibly store downloaded archive + unpacked data on the same disk
                 let required = if free_space::is_same_disk(&temp_path, &unpack_to) {
-                    (required as f64 * 2.5).ceil() as u64
+                    required_space(required, downloaded)
                 } else {
-                    required
+                    required.saturating_sub(downloaded)
                 };
 
-                // Sub downloaded size from the required one
-                let required = required.checked_sub(downloaded)
-                    .unwrap_or_default();
-
                 if space < required {
                     tracing::error!("No free space available in the temp folder. Required: {required}. Available: {space}");
 
-                    (updater)(DownloadingError::NoSpaceAvailable(temp_path, required, space).into());
+                    (updater)(DownloadingError::NoSpaceAvailable(temp_path.clone(), required, space).into());
 
-                    return;
+                    return Err(DownloadingError::NoSpaceAvailable(temp_path, required, space).into());
                 }
             }
 
-            // Check available free space for unpacked archvie data (archive size * 1.5)
+            // Check available free space for unpacked archvie data
             (updater)(Update::CheckingFreeSpace(unpack_to.clone()));
 
             let Some(space) = free_space::available(&unpack_to) else {
                 tracing::error!("Path is not mounted: {:?}", temp_path);
 
-                (updater)(DownloadingError::PathNotMounted(unpack_to).into());
+                (updater)(DownloadingError::PathNotMounted(unpack_to.clone()).into());
 
-                return;
+                return Err(DownloadingError::PathNotMounted(unpack_to).into());
             };
 
             if let Some(required) = self.downloader.length() {
                 // We can possibly store downloaded archive + unpacked data on the same disk
                 let required = if free_space::is_same_disk(&unpack_to, &temp_path) {
-                    (required as f64 * 2.5).ceil() as u64
+                    required_space(required, downloaded)
                 } else {
-                    (required as f64 * 1.5).ceil() as u64
+                    unpacked_size(required)
                 };
 
                 if space < required {
                     tracing::error!("No free space available in the installation folder. Required: {required}. Available: {space}");
 
-                    (updater)(DownloadingError::NoSpaceAvailable(unpack_to, required, space).into());
+                    (updater)(DownloadingError::NoSpaceAvailable(unpack_to.clone(), required, space).into());
 
-                    return;
+                    return Err(DownloadingError::NoSpaceAvailable(unpack_to, required, space).into());
                 }
             }
         }
@@ -196,112 +388,267 @@ impl Installer {
         if let Err(err) = self.downloader.download(&temp_path, move |curr, total| (download_progress_updater)(Update::DownloadingProgress(curr, total))) {
             tracing::error!("Failed to download archive: {err}");
 
-            (updater)(Update::DownloadingError(err));
+            (updater)(Update::DownloadingError(err.clone()));
 
-            return;
+            return Err(err.into());
         }
 
         (updater)(Update::DownloadingFinished);
 
-        match Archive::open(&temp_path) {
-            Ok(mut archive) => {
-                // Temporary workaround as we can't get archive extraction process
-                // directly - we'll spawn it in another thread and check this archive entries appearence in the filesystem
-                let mut total = 0;
+        let entries = match Archive::open(&temp_path).and_then(|mut archive| archive.get_entries()) {
+            Ok(entries) => entries,
 
-                let entries = archive
-                    .get_entries()
-                    .expect("Failed to get archive entries");
+            Err(err) => {
+                tracing::error!("Failed to open archive: {err}");
 
-                let entries_number = entries.len() as u64;
+                (updater)(Update::UnpackingError(err.to_string()));
 
-                (updater)(Update::UpdatingPermissionsStarted(unpack_to.clone()));
+                return Err(InstallerError::UnpackingError(err.to_string()));
+            }
+        };
 
-                for (i, entry) in entries.iter().enumerate() {
-                    total += entry.size.get_size();
+        // Temporary workaround as we can't get archive extraction process
+        // directly - we'll spawn it in another thread and check this archive entries appearence in the filesystem
+        let mut total = 0;
 
-                    let path = unpack_to.join(&entry.name);
+        let entries_number = entries.len() as u64;
 
-                    // Failed to change permissions => likely patch-related file and was made by the sudo, so root
-                    #[allow(unused_must_use)]
-                    if let Err(_) = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o666)) {
-                        // For weird reason we can delete files made by root, but can't modify their permissions
-                        // We're not checking its result because if it's error - then it's either couldn't be removed (which is not the case)
-                        // or the file doesn't exist, which we obviously can just ignore
-                        std::fs::remove_file(&path);
-                    }
+        (updater)(Update::UpdatingPermissionsStarted(unpack_to.clone()));
+
+        for (i, entry) in entries.iter().enumerate() {
+            total += entry.size.get_size();
+
+            let path = unpack_to.join(&entry.name);
+
+            // Failed to change permissions => likely patch-related file and was made by the sudo, so root
+            #[allow(unused_must_use)]
+            if let Err(_) = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o666)) {
+                // For weird reason we can delete files made by root, but can't modify their permissions
+                // We're not checking its result because if it's error - then it's either couldn't be removed (which is not the case)
+                // or the file doesn't exist, which we obviously can just ignore
+                std::fs::remove_file(&path);
+            }
+
+            (updater)(Update::UpdatingPermissions(i as u64 + 1, entries_number));
+        }
+
+        (updater)(Update::UpdatingPermissionsFinished);
+
+        // Files which will be created by the extraction and should be removed if it fails
+        let mut created = HashSet::new();
 
-                    (updater)(Update::UpdatingPermissions(i as u64 + 1, entries_number));
+        if self.rollback {
+            for entry in &entries {
+                let path = unpack_to.join(&entry.name);
+
+                if !path.exists() {
+                    created.insert(path);
                 }
+            }
+        }
+
+        let rollback_manifest = Arc::new(Mutex::new(RollbackManifest::default()));
+        let extraction_finished = Arc::new(AtomicBool::new(false));
+
+        tracing::trace!("Extracting archive");
 
-                (updater)(Update::UpdatingPermissionsFinished);
+        let unpacking_path = unpack_to.clone();
+        let unpacking_updater = updater.clone();
+        let unpacking_manifest = rollback_manifest.clone();
+        let unpacking_finished = extraction_finished.clone();
 
-                tracing::trace!("Extracting archive");
+        let handle_2 = std::thread::spawn(move || {
+            let mut entries = entries.into_iter()
+                .map(|entry| (unpacking_path.join(&entry.name), entry.size.get_size(), true))
+                .collect::<Vec<_>>();
 
-                let unpacking_path = unpack_to.clone();
-                let unpacking_updater = updater.clone();
+            let mut unpacked = 0;
 
-                let handle_2 = std::thread::spawn(move || {
-                    let mut entries = entries.into_iter()
-                        .map(|entry| (unpacking_path.join(&entry.name), entry.size.get_size(), true))
-                        .collect::<Vec<_>>();
+            loop {
+                std::thread::sleep(std::time::Duration::from_millis(250));
 
-                    let mut unpacked = 0;
+                // Checked before going through the entries so the last
+                // iteration sees all the files written by the extraction
+                let finished = unpacking_finished.load(Ordering::Acquire);
 
-                    loop {
-                        std::thread::sleep(std::time::Duration::from_millis(250));
+                let mut empty = true;
 
-                        let mut empty = true;
+                for (path, size, remained) in &mut entries {
+                    if *remained {
+                        empty = false;
 
-                        for (path, size, remained) in &mut entries {
-                            if *remained {
-                                empty = false;
+                        if path.exists() {
+                            *remained = false;
 
-                                if std::path::Path::new(path).exists() {
-                                    *remained = false;
+                            unpacked += *size;
 
-                                    unpacked += *size;
+                            if created.contains(path) {
+                                if let Ok(mut manifest) = unpacking_manifest.lock() {
+                                    manifest.0.push(path.clone());
                                 }
                             }
                         }
+                    }
+                }
 
-                        (unpacking_updater)(Update::UnpackingProgress(unpacked, total));
+                (unpacking_updater)(Update::UnpackingProgress(unpacked, total));
 
-                        if empty {
-                            break;
+                if empty || finished {
+                    break;
+                }
+            }
+        });
+
+        // Run archive extraction in another thread to not to freeze the current one
+        let handle_1 = std::thread::spawn(move || {
+            (updater)(Update::UnpackingStarted(unpack_to.clone()));
+
+            // We have to create new instance of Archive here
+            // because otherwise it may not work after get_entries method call
+            let result = match Archive::open(&temp_path) {
+                Ok(mut archive) => match archive.extract(unpack_to) {
+                    Ok(_) => {
+                        // TODO error handling
+                        #[allow(unused_must_use)] {
+                            std::fs::remove_file(temp_path);
                         }
-                    }
-                });
-
-                // Run archive extraction in another thread to not to freeze the current one
-                let handle_1 = std::thread::spawn(move || {
-                    (updater)(Update::UnpackingStarted(unpack_to.clone()));
-
-                    // We have to create new instance of Archive here
-                    // because otherwise it may not work after get_entries method call
-                    match Archive::open(&temp_path) {
-                        Ok(mut archive) => match archive.extract(unpack_to) {
-                            Ok(_) => {
-                                // TODO error handling
-                                #[allow(unused_must_use)] {
-                                    std::fs::remove_file(temp_path);
-                                }
 
-                                (updater)(Update::UnpackingFinished);
-                            }
+                        (updater)(Update::UnpackingFinished);
 
-                            Err(err) => (updater)(Update::UnpackingError(err.to_string()))
-                        }
+                        Ok(())
+                    }
 
-                        Err(err) => (updater)(Update::UnpackingError(err.to_string()))
+                    Err(err) => {
+                        (updater)(Update::UnpackingError(err.to_string()));
+
+                        Err(err.to_string())
                     }
-                });
+                }
+
+                Err(err) => {
+                    (updater)(Update::UnpackingError(err.to_string()));
+
+                    Err(err.to_string())
+                }
+            };
+
+            extraction_finished.store(true, Ordering::Release);
+
+            result
+        });
+
+        let result = handle_1.join()
+            .unwrap_or_else(|_| Err(String::from("Archive extraction thread panicked")));
 
-                handle_1.join().unwrap();
-                handle_2.join().unwrap();
+        handle_2.join().unwrap();
+
+        let mut manifest = rollback_manifest.lock()
+            .map(|mut manifest| std::mem::take(&mut *manifest))
+            .unwrap_or_default();
+
+        match result {
+            Ok(()) => {
+                // Keep extracted files
+                manifest.0.clear();
+
+                Ok(())
             }
 
-            Err(err) => (updater)(Update::UnpackingError(err.to_string()))
+            Err(err) => {
+                tracing::error!("Failed to extract archive: {err}");
+
+                // Remove files created by the failed extraction
+                drop(manifest);
+
+                Err(InstallerError::UnpackingError(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollback() -> anyhow::Result<()> {
+        let folder = std::env::temp_dir().join(".anime-game-core-test-installer-rollback");
+        let archive = folder.join("example.tar");
+        let unpack_to = folder.join("output");
+
+        std::fs::create_dir_all(&folder)?;
+
+        let mut builder = tar::Builder::new(Vec::new());
+
+        for (name, size) in [("a.txt", 16), ("b.txt", 4096)] {
+            let mut header = tar::Header::new_gnu();
+
+            header.set_size(size as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+
+            builder.append_data(&mut header, name, &vec![b'a'; size][..])?;
         }
+
+        // Cut the second file's data to make extraction fail after the first file is written
+        let mut bytes = builder.into_inner()?;
+
+        bytes.truncate(512 * 3 + 128);
+
+        std::fs::write(&archive, bytes)?;
+
+        let result = Installer::new(format!("file://{}", archive.to_string_lossy()))?
+            .with_temp_folder(folder.join("temp"))
+            .with_free_space_check(false)
+            .with_rollback(true)
+            .install(&unpack_to, |_| {});
+
+        assert!(matches!(result, Err(InstallerError::UnpackingError(_))));
+        assert_eq!(unpack_to.read_dir()?.count(), 0);
+
+        std::fs::remove_dir_all(folder)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dry_run() -> anyhow::Result<()> {
+        let folder = std::env::temp_dir().join(".anime-game-core-test-installer-dry-run");
+        let archive = folder.join("example.tar");
+
+        std::fs::create_dir_all(&folder)?;
+
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+
+        header.set_size(16);
+        header.set_mode(0o644);
+        header.set_cksum();
+
+        builder.append_data(&mut header, "a.txt", &[b'a'; 16][..])?;
+
+        std::fs::write(&archive, builder.into_inner()?)?;
+
+        let length = archive.metadata()?.len();
+
+        let mut installer = Installer::new(format!("file://{}", archive.to_string_lossy()))?
+            .with_temp_folder(folder.join("temp"));
+
+        // Archive is not downloaded yet
+        let report = installer.dry_run()?;
+
+        assert_eq!(report.required_bytes, required_space(length, 0));
+        assert_eq!(report.files, None);
+
+        installer.downloader.download(installer.get_temp_path(), |_, _| {})?;
+
+        let report = installer.dry_run()?;
+
+        assert_eq!(report.file_count, Some(1));
+        assert_eq!(report.files, Some(vec![PathBuf::from("a.txt")]));
+
+        std::fs::remove_dir_all(folder)?;
+
+        Ok(())
     }
 }
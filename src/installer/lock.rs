@@ -0,0 +1,163 @@
+use std::path::{Path, PathBuf};
+use std::fs::File;
+use std::io::Write;
+use std::os::unix::fs::MetadataExt;
+
+use nix::fcntl::{Flock, FlockArg};
+use nix::errno::Errno;
+
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+
+/// Name of the lock file created in the installation folder
+pub const LOCK_FILE_NAME: &str = ".anime-game-core.lock";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LockError {
+    /// Another installation process already works with this folder
+    ///
+    /// `(lock file path)`
+    #[error("Installation folder is already locked: {0:?}")]
+    AlreadyLocked(PathBuf),
+
+    /// Failed to create lock file
+    ///
+    /// `(lock file path, error message)`
+    #[error("Failed to create lock file {0:?}: {1}")]
+    LockFileError(PathBuf, String)
+}
+
+/// Lock file used to prevent concurrent installations into the same folder
+///
+/// The lock is held using `flock` so it's released by the system if the process
+/// has crashed, and left lock files don't block future installations.
+///
+/// The lock is exclusive even within the current process. Functions which
+/// install multiple things into the same folder should take the lock once
+/// and pass it down (see `LockFile::locks`).
+///
+/// The file is removed when the value is dropped
+#[derive(Debug)]
+pub struct LockFile {
+    folder: PathBuf,
+    path: PathBuf,
+
+    // Never read, but the lock is released when it's dropped
+    _lock: Flock<File>
+}
+
+impl LockFile {
+    /// Create lock file in the specified folder
+    ///
+    /// Returns `LockError::AlreadyLocked` if the folder is already locked,
+    /// including by the current process
+    pub fn new(folder: impl AsRef<Path>) -> Result<Self, LockError> {
+        let folder = folder.as_ref();
+        let path = folder.join(LOCK_FILE_NAME);
+
+        if !folder.exists() {
+            if let Err(err) = std::fs::create_dir_all(folder) {
+                return Err(LockError::LockFileError(path, err.to_string()));
+            }
+        }
+
+        loop {
+            let file = std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)
+                .map_err(|err| LockError::LockFileError(path.clone(), err.to_string()))?;
+
+            let mut lock = match Flock::lock(file, FlockArg::LockExclusiveNonblock) {
+                Ok(lock) => lock,
+
+                Err((_, Errno::EWOULDBLOCK)) => return Err(LockError::AlreadyLocked(path)),
+                Err((_, err)) => return Err(LockError::LockFileError(path, err.to_string()))
+            };
+
+            // Previous owner could remove the file after we opened it,
+            // so we have to make sure that we locked the actual one
+            let locked_actual_file = match (lock.metadata(), path.metadata()) {
+                (Ok(locked), Ok(actual)) => locked.dev() == actual.dev() && locked.ino() == actual.ino(),
+                _ => false
+            };
+
+            if !locked_actual_file {
+                continue;
+            }
+
+            // Store owner PID for debugging purposes
+            let result = lock.set_len(0)
+                .and_then(|_| lock.write_all(std::process::id().to_string().as_bytes()));
+
+            if let Err(err) = result {
+                tracing::warn!("Failed to write PID to the lock file {:?}: {err}", path);
+            }
+
+            return Ok(Self {
+                folder: folder.to_path_buf(),
+                path,
+                _lock: lock
+            });
+        }
+    }
+
+    #[inline]
+    /// Get path to the lock file
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    #[inline]
+    /// Check if this lock is held for the given folder
+    pub fn locks(&self, folder: impl AsRef<Path>) -> bool {
+        self.folder == folder.as_ref()
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        // The file is removed while it's still locked so other processes
+        // can't lock it in between
+        if let Err(err) = std::fs::remove_file(&self.path) {
+            tracing::warn!("Failed to remove lock file {:?}: {err}", self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_file() -> Result<(), LockError> {
+        let folder = std::env::temp_dir().join(".anime-game-core-test-lock-file");
+        let path = folder.join(LOCK_FILE_NAME);
+
+        // Lock file left by a crashed process
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(&path, "0").unwrap();
+
+        let lock = LockFile::new(&folder)?;
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), std::process::id().to_string());
+
+        assert!(lock.locks(&folder));
+
+        // Lock is exclusive within the process too
+        assert_eq!(LockFile::new(&folder).unwrap_err(), LockError::AlreadyLocked(path.clone()));
+
+        drop(lock);
+
+        assert!(!path.exists());
+
+        // Lock is released
+        drop(LockFile::new(&folder)?);
+
+        std::fs::remove_dir_all(folder).unwrap();
+
+        Ok(())
+    }
+}
@@ -2,9 +2,13 @@ pub mod downloader;
 pub mod archives;
 pub mod installer;
 pub mod free_space;
+pub mod lock;
 
 pub mod prelude {
-    pub use super::archives::Archive;
+    pub use super::archives::{
+        Archive,
+        ArchiveFormat
+    };
     pub use super::free_space;
 
     pub use super::downloader::{
@@ -12,8 +16,15 @@ pub mod prelude {
         DownloadingError
     };
 
+    pub use super::lock::{
+        LockFile,
+        LockError
+    };
+
     pub use super::installer::{
         Installer,
+        InstallerError,
+        DryRunReport,
         Update as InstallerUpdate
     };
 }
@@ -9,12 +9,50 @@ lazy_static::lazy_static! {
         Ok(timeout) => timeout.parse().unwrap_or(8),
         Err(_) => 8
     };
+
+    /// User agent used by all the requests made by the library
+    static ref USER_AGENT: std::sync::RwLock<Option<String>> = std::sync::RwLock::new(None);
+}
+
+/// Set user agent used by all the requests made by the library
+pub fn set_user_agent(user_agent: impl ToString) {
+    if let Ok(mut value) = USER_AGENT.write() {
+        *value = Some(user_agent.to_string());
+    }
+}
+
+/// Apply library-wide settings to the request
+pub(crate) fn request(request: minreq::Request) -> minreq::Request {
+    match USER_AGENT.read() {
+        Ok(user_agent) => match user_agent.as_deref() {
+            Some(user_agent) => request.with_header("user-agent", user_agent),
+            None => request
+        }
+
+        Err(_) => request
+    }
+}
+
+#[inline]
+/// Get requests timeout in seconds, or `REQUESTS_TIMEOUT` if not specified
+pub(crate) fn requests_timeout(timeout: Option<std::time::Duration>) -> u64 {
+    timeout.map(|timeout| timeout.as_secs().max(1))
+        .unwrap_or(*REQUESTS_TIMEOUT)
 }
 
 pub mod version;
 pub mod traits;
 pub mod prettify_bytes;
 pub mod check_domain;
+pub mod disk_cache;
+
+#[cfg(any(feature = "genshin", feature = "star-rail", feature = "zzz", feature = "honkai"))]
+pub mod api;
+
+pub mod telemetry;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
 
 #[cfg(feature = "patches")]
 pub mod patches;
@@ -52,6 +90,9 @@ pub mod installer;
 #[cfg(feature = "install")]
 pub mod repairer;
 
+#[cfg(feature = "install")]
+pub mod hash;
+
 pub mod prelude {
     pub use super::version::*;
     pub use super::prettify_bytes::prettify_bytes;
@@ -32,7 +32,7 @@ pub fn get_version(folder: impl AsRef<Path>) -> anyhow::Result<Version> {
 #[cfg(feature = "install")]
 #[cached::proc_macro::cached(result)]
 pub fn get_latest() -> anyhow::Result<JadeiteLatest> {
-    let response = minreq::get(REPO_API_URI).send()?.json::<serde_json::Value>()?;
+    let response = crate::request(minreq::get(REPO_API_URI)).send()?.json::<serde_json::Value>()?;
 
     let version = response.get("tag_name")
         .and_then(|tag| tag.as_str())
@@ -59,11 +59,34 @@ pub fn get_latest() -> anyhow::Result<JadeiteLatest> {
     })
 }
 
+#[cfg(feature = "install")]
+/// Compare installed patch version with the latest one
+pub fn check_update(folder: impl AsRef<Path>) -> anyhow::Result<JadeiteUpdateStatus> {
+    if !is_installed(&folder) {
+        return Ok(JadeiteUpdateStatus::NotInstalled);
+    }
+
+    let current = get_version(folder)?;
+    let latest = get_latest()?;
+
+    if current >= latest.version {
+        Ok(JadeiteUpdateStatus::UpToDate)
+    }
+
+    else {
+        Ok(JadeiteUpdateStatus::UpdateAvailable {
+            current,
+            latest: latest.version,
+            download_uri: latest.download_uri
+        })
+    }
+}
+
 #[cfg(feature = "install")]
 #[cached::proc_macro::cached(result)]
 pub fn get_metadata() -> anyhow::Result<metadata::JadeiteMetadata> {
     for uri in METADATA_URIS {
-        let Ok(resp) = minreq::get(*uri).send() else {
+        let Ok(resp) = crate::request(minreq::get(*uri)).send() else {
             tracing::warn!("Could not reach '{uri}'. Attempting to use next fallback");
             continue;
         };
@@ -79,6 +102,36 @@ pub fn get_metadata() -> anyhow::Result<metadata::JadeiteMetadata> {
     anyhow::bail!("Could not get metadata from any of the mirrors");
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JadeiteUpdateStatus {
+    /// Installed patch version is the latest one
+    UpToDate,
+
+    UpdateAvailable {
+        current: Version,
+        latest: Version,
+        download_uri: String
+    },
+
+    NotInstalled
+}
+
+/// Patch installation progress sent by `JadeiteLatest::install_with_progress`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JadeiteUpdate {
+    Downloading {
+        current: u64,
+        total: u64
+    },
+
+    Extracting {
+        current: u64,
+        total: u64
+    },
+
+    Installed
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct JadeiteLatest {
     pub version: Version,
@@ -88,13 +141,59 @@ pub struct JadeiteLatest {
 impl JadeiteLatest {
     #[cfg(feature = "install")]
     pub fn install(&self, folder: impl AsRef<Path>, updater: impl Fn(Update) + Clone + Send + 'static) -> anyhow::Result<()> {
-        Installer::new(&self.download_uri)?
+        let installer = Installer::new(&self.download_uri)?
+            .with_filename("jadeite.zip")
+            .with_free_space_check(false);
+
+        self.install_with(installer, folder.as_ref(), updater)
+    }
+
+    #[cfg(feature = "install")]
+    /// Install the patch reporting jadeite-specific progress
+    ///
+    /// Unpacked files are removed and `.version` file is not written if the installation has failed
+    pub fn install_with_progress(&self, folder: impl AsRef<Path>, updater: impl Fn(JadeiteUpdate) + Clone + Send + 'static) -> anyhow::Result<()> {
+        let installer_updater = updater.clone();
+
+        let installer = Installer::new(&self.download_uri)?
             .with_filename("jadeite.zip")
             .with_free_space_check(false)
-            .install(folder.as_ref(), updater);
+            .with_rollback(true);
+
+        self.install_with(installer, folder.as_ref(), move |update| match update {
+            Update::DownloadingProgress(current, total) => (installer_updater)(JadeiteUpdate::Downloading { current, total }),
+            Update::UnpackingProgress(current, total) => (installer_updater)(JadeiteUpdate::Extracting { current, total }),
+
+            _ => ()
+        }).map_err(|err| anyhow::anyhow!("Failed to install jadeite: {err}"))?;
 
-        std::fs::write(folder.as_ref().join(".version"), self.version.version)?;
+        (updater)(JadeiteUpdate::Installed);
 
         Ok(())
     }
+
+    #[cfg(feature = "install")]
+    /// Unpack the patch to the temporary folder and move it
+    /// to the given one only when it's completely written
+    fn install_with(&self, mut installer: Installer, folder: &Path, updater: impl Fn(Update) + Clone + Send + 'static) -> anyhow::Result<()> {
+        let tmp_folder = super::tmp_path(folder);
+
+        // Remove folder left by interrupted installation
+        if tmp_folder.exists() {
+            std::fs::remove_dir_all(&tmp_folder)?;
+        }
+
+        let result = installer.install(&tmp_folder, updater)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| Ok(std::fs::write(tmp_folder.join(".version"), self.version.version)?))
+            .and_then(|_| Ok(super::atomic_replace_dir(&tmp_folder, folder)?));
+
+        if result.is_err() && tmp_folder.exists() {
+            #[allow(unused_must_use)] {
+                std::fs::remove_dir_all(&tmp_folder);
+            }
+        }
+
+        result
+    }
 }
@@ -64,11 +64,14 @@ pub fn install(wine_prefix: impl AsRef<Path>, temp: Option<impl Into<PathBuf>>)
         anyhow::bail!("Failed to extract vcredist (2): {}", String::from_utf8_lossy(&output.stderr));
     }
 
+    let system32 = wine_prefix.as_ref().join("drive_c/windows/system32");
+
+    // Remove libraries left half-copied by previous installation attempts
+    super::remove_tmp_files(&system32)?;
+
     // w_try_cp_dll "${W_TMP}/win64"/mfc140.dll "${W_SYSTEM64_DLLS}"/mfc140.dll
     for lib in LIBRARIES {
-        let dest = wine_prefix.as_ref().join("drive_c/windows/system32").join(lib);
-
-        std::fs::copy(vcredist_extracted.join(lib), dest)?;
+        super::atomic_copy(vcredist_extracted.join(lib), system32.join(lib))?;
     }
 
     std::fs::remove_dir_all(temp)?;
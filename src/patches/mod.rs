@@ -1,3 +1,7 @@
+use std::path::{Path, PathBuf};
+use std::fs::File;
+use std::io::Write;
+
 #[cfg(feature = "patch-jadeite")]
 pub mod jadeite;
 
@@ -7,11 +11,98 @@ pub mod mfc140;
 #[cfg(feature = "patch-vcrun2015")]
 pub mod vcrun2015;
 
+/// Suffix of the temporary files written by patches before moving them to the final location
+pub const PATCH_TMP_SUFFIX: &str = ".patch_tmp";
+
+/// Get temporary path used to write the file
+///
+/// - `/path/to/file.dll` -> `/path/to/file.dll.patch_tmp`
+pub fn tmp_path(path: impl AsRef<Path>) -> PathBuf {
+    let mut path = path.as_ref().as_os_str().to_os_string();
+
+    path.push(PATCH_TMP_SUFFIX);
+
+    PathBuf::from(path)
+}
+
+/// Write file content to the temporary path and move it to the given one
+/// after it was completely written and synced
+///
+/// This prevents leaving the file half-written if the process is killed
+pub fn atomic_write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let tmp = tmp_path(&path);
+
+    let mut file = File::create(&tmp)?;
+
+    file.write_all(contents.as_ref())?;
+    file.sync_all()?;
+
+    std::fs::rename(tmp, path)
+}
+
+/// Copy file to the temporary path and move it to the given one
+/// after it was completely written and synced
+pub fn atomic_copy(from: impl AsRef<Path>, to: impl AsRef<Path>) -> std::io::Result<()> {
+    let tmp = tmp_path(&to);
+
+    std::fs::copy(from, &tmp)?;
+
+    File::open(&tmp)?.sync_all()?;
+
+    std::fs::rename(tmp, to)
+}
+
+/// Sync all the files in the folder recursively
+fn sync_dir(path: impl AsRef<Path>) -> std::io::Result<()> {
+    for entry in path.as_ref().read_dir()? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            sync_dir(path)?;
+        }
+
+        else {
+            File::open(path)?.sync_all()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Move completely written folder to the given path, replacing the existing one
+///
+/// All the files are synced before the folder is moved, so the destination
+/// either keeps its previous content or gets the new one entirely
+pub fn atomic_replace_dir(from: impl AsRef<Path>, to: impl AsRef<Path>) -> std::io::Result<()> {
+    sync_dir(&from)?;
+
+    if to.as_ref().exists() {
+        std::fs::remove_dir_all(&to)?;
+    }
+
+    std::fs::rename(from, to)
+}
+
+/// Remove temporary files left in the folder by interrupted patches applying
+pub fn remove_tmp_files(folder: impl AsRef<Path>) -> std::io::Result<()> {
+    for entry in folder.as_ref().read_dir()? {
+        let path = entry?.path();
+
+        if path.to_string_lossy().ends_with(PATCH_TMP_SUFFIX) {
+            std::fs::remove_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
 pub mod prelude {
     #[cfg(feature = "patch-jadeite")]
     pub use super::jadeite::{
         self,
         JadeiteLatest,
+        JadeiteUpdateStatus,
+        JadeiteUpdate,
         metadata::*
     };
 
@@ -21,3 +112,56 @@ pub mod prelude {
     #[cfg(feature = "patch-vcrun2015")]
     pub use super::vcrun2015;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atomic_write() -> std::io::Result<()> {
+        let folder = std::env::temp_dir().join(".anime-game-core-test-atomic-write");
+
+        std::fs::create_dir_all(&folder)?;
+
+        let path = folder.join("user.reg");
+
+        atomic_write(&path, "Hello, World!")?;
+
+        assert_eq!(std::fs::read_to_string(&path)?, "Hello, World!");
+        assert!(!tmp_path(&path).exists());
+
+        std::fs::write(tmp_path(folder.join("mfc140.dll")), "")?;
+
+        remove_tmp_files(&folder)?;
+
+        assert!(path.exists());
+        assert!(!tmp_path(folder.join("mfc140.dll")).exists());
+
+        std::fs::remove_dir_all(folder)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_atomic_replace_dir() -> std::io::Result<()> {
+        let folder = std::env::temp_dir().join(".anime-game-core-test-atomic-replace-dir");
+        let tmp_folder = tmp_path(&folder);
+
+        std::fs::create_dir_all(&folder)?;
+        std::fs::create_dir_all(tmp_folder.join("nested"))?;
+
+        std::fs::write(folder.join(".version"), "old")?;
+        std::fs::write(tmp_folder.join(".version"), "new")?;
+        std::fs::write(tmp_folder.join("nested/jadeite.exe"), "")?;
+
+        atomic_replace_dir(&tmp_folder, &folder)?;
+
+        assert_eq!(std::fs::read_to_string(folder.join(".version"))?, "new");
+        assert!(folder.join("nested/jadeite.exe").exists());
+        assert!(!tmp_folder.exists());
+
+        std::fs::remove_dir_all(folder)?;
+
+        Ok(())
+    }
+}
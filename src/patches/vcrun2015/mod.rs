@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 use wincompatlib::wine::ext::*;
 
 use crate::installer::downloader::Downloader;
+use crate::version::Version;
 
 // Source: https://github.com/Winetricks/winetricks/blob/8ffdb53f5aebfe51502ecceb0d5e7994ad814424/src/winetricks#L13702
 // TODO: consider moving it to the wincompatlib
@@ -37,9 +38,97 @@ const LIBRARIES: &[&str] = &[
     "vcruntime140_1"
 ];
 
+/// `VS_FIXEDFILEINFO` structure signature
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF04BD;
+
+/// `VS_FIXEDFILEINFO` structure version
+const FIXED_FILE_INFO_VERSION: u32 = 0x00010000;
+
+/// `VS_FIXEDFILEINFO` structure size
+const FIXED_FILE_INFO_SIZE: usize = 52;
+
+/// `L"VS_VERSION_INFO"` key with the null terminator
+const VERSION_INFO_KEY: &[u8] = b"V\0S\0_\0V\0E\0R\0S\0I\0O\0N\0_\0I\0N\0F\0O\0\0\0";
+
+/// Strings stored in wine's own fake DLLs
+const WINE_DLL_MARKERS: &[&[u8]] = &[
+    b"Wine placeholder DLL",
+    b"Wine builtin DLL"
+];
+
+/// Read `major.minor` file version from the PE file's `VS_VERSION_INFO` resource
+///
+/// Build number doesn't fit the `Version` struct so it's set to 0
+fn parse_file_version(dll: &[u8]) -> Option<Version> {
+    let read_u16 = |offset: usize| dll.get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]));
+
+    let read_u32 = |offset: usize| dll.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+
+    let mut keys = dll.windows(VERSION_INFO_KEY.len())
+        .enumerate()
+        .filter(|(offset, window)| *offset >= 6 && *window == VERSION_INFO_KEY)
+        .map(|(offset, _)| offset);
+
+    // The key can appear in the file outside of the version resource,
+    // so look for the one which is followed by a valid VS_FIXEDFILEINFO
+    keys.find_map(|key| {
+        // wLength, wValueLength, wType, szKey
+        let start = key - 6;
+
+        // Resource structures are 32-bit aligned
+        if start % 4 != 0 {
+            return None;
+        }
+
+        let length = read_u16(start)? as usize;
+        let value_length = read_u16(start + 2)? as usize;
+
+        // Value is aligned to 32 bits after the key
+        let value = (key + VERSION_INFO_KEY.len() + 3) & !3;
+
+        if value_length != FIXED_FILE_INFO_SIZE || length < value - start + FIXED_FILE_INFO_SIZE {
+            return None;
+        }
+
+        // dwSignature, dwStrucVersion
+        if read_u32(value)? != FIXED_FILE_INFO_SIGNATURE || read_u32(value + 4)? != FIXED_FILE_INFO_VERSION {
+            return None;
+        }
+
+        // dwFileVersionMS
+        let version = read_u32(value + 8)?;
+
+        let major = version >> 16;
+        let minor = version & 0xFFFF;
+
+        Some(Version::new(major.try_into().ok()?, minor.try_into().ok()?, 0))
+    })
+}
+
+/// Get version of the installed `vcruntime140.dll`
+///
+/// Return `None` if the library is not installed, is a wine's builtin one,
+/// or has no version resource
+pub fn installed_version(wine_prefix: impl AsRef<Path>) -> Option<Version> {
+    let dll = std::fs::read(wine_prefix.as_ref().join("drive_c/windows/system32/vcruntime140.dll")).ok()?;
+
+    let is_wine_dll = WINE_DLL_MARKERS.iter()
+        .any(|marker| dll.windows(marker.len()).any(|window| window == *marker));
+
+    if is_wine_dll {
+        return None;
+    }
+
+    parse_file_version(&dll)
+}
+
+/// Check that native `vcruntime140.dll` from VC++ 2015-2022 redistributable (version 14.x) is installed
 pub fn is_installed(wine_prefix: impl AsRef<Path>) -> bool {
-    // Not listed above but it must be installed too
-    wine_prefix.as_ref().join("drive_c/windows/system32/mfc140.dll").exists()
+    installed_version(wine_prefix)
+        .map(|version| version.version[0] == 14)
+        .unwrap_or(false)
 }
 
 pub fn install(wine: impl WineWithExt + WineRunExt, wine_prefix: impl AsRef<Path>, temp: Option<impl Into<PathBuf>>) -> anyhow::Result<()> {
@@ -75,27 +164,176 @@ pub fn install(wine: impl WineWithExt + WineRunExt, wine_prefix: impl AsRef<Path
     let reg_file = wine_prefix.as_ref().join("user.reg");
 
     let reg = std::fs::read_to_string(&reg_file)?;
-    let mut new_reg = String::new();
 
-    for record in reg.split("\n\n") {
-        if record.starts_with("[Software\\\\Wine\\\\DllOverrides]") {
-            let mut new_record = record.to_string();
+    // Remove registry file left half-written by previous installation attempts
+    super::remove_tmp_files(wine_prefix.as_ref())?;
 
-            for lib in LIBRARIES {
-                if !new_record.contains(lib) {
-                    new_record = format!("{new_record}\n\"{lib}\"=\"native,builtin\"");
-                }
-            }
+    super::atomic_write(reg_file, add_dll_overrides(&reg, LIBRARIES))?;
+
+    Ok(())
+}
+
+/// Add `native,builtin` overrides for the libraries to the `user.reg` file's content
+///
+/// Existing overrides are kept as is. Overrides section is created
+/// if it doesn't exist. Both `\n` and `\r\n` line endings are supported
+fn add_dll_overrides(reg: &str, libraries: &[&str]) -> String {
+    const SECTION: &str = "[Software\\\\Wine\\\\DllOverrides]";
+
+    let line_ending = if reg.contains("\r\n") { "\r\n" } else { "\n" };
+
+    let mut lines = reg.lines().map(String::from).collect::<Vec<_>>();
 
-            new_reg = format!("{new_reg}{new_record}\n\n");
+    let section = lines.iter().position(|line| line.starts_with(SECTION));
+
+    let (section_start, section_end) = match section {
+        Some(start) => {
+            let end = lines[start + 1..].iter()
+                .position(|line| line.starts_with('['))
+                .map(|end| start + 1 + end)
+                .unwrap_or(lines.len());
+
+            (start, end)
         }
 
-        else {
-            new_reg = format!("{new_reg}{record}\n\n");
+        None => {
+            if lines.last().is_some_and(|line| !line.is_empty()) {
+                lines.push(String::new());
+            }
+
+            lines.push(SECTION.to_string());
+            lines.push(String::new());
+
+            (lines.len() - 2, lines.len())
         }
+    };
+
+    // Registry keys are case insensitive
+    let existing = lines[section_start + 1..section_end].iter()
+        .filter_map(|line| line.strip_prefix('"'))
+        .filter_map(|line| line.split_once('"'))
+        .map(|(key, _)| key.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+
+    let missing = libraries.iter()
+        .filter(|lib| !existing.contains(&lib.to_ascii_lowercase()))
+        .map(|lib| format!("\"{lib}\"=\"native,builtin\""))
+        .collect::<Vec<_>>();
+
+    // Insert new overrides after the last non-empty line of the section
+    let insert_at = lines[section_start..section_end].iter()
+        .rposition(|line| !line.is_empty())
+        .map(|pos| section_start + pos + 1)
+        .unwrap_or(section_end);
+
+    lines.splice(insert_at..insert_at, missing);
+
+    let mut reg = lines.join(line_ending);
+
+    reg.push_str(line_ending);
+
+    reg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Beginning of the version resource of the `vcruntime140.dll` 14.29.30139.0
+    const VCRUNTIME140_VERSION_INFO: &[u8] = &[
+        // wLength, wValueLength, wType
+        0x7C, 0x03, 0x34, 0x00, 0x00, 0x00,
+
+        // szKey, Padding1
+        0x56, 0x00, 0x53, 0x00, 0x5F, 0x00, 0x56, 0x00, 0x45, 0x00, 0x52, 0x00, 0x53, 0x00, 0x49, 0x00,
+        0x4F, 0x00, 0x4E, 0x00, 0x5F, 0x00, 0x49, 0x00, 0x4E, 0x00, 0x46, 0x00, 0x4F, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+
+        // dwSignature, dwStrucVersion
+        0xBD, 0x04, 0xEF, 0xFE, 0x00, 0x00, 0x01, 0x00,
+
+        // dwFileVersionMS, dwFileVersionLS, dwProductVersionMS, dwProductVersionLS
+        0x1D, 0x00, 0x0E, 0x00, 0x00, 0x00, 0xBB, 0x75, 0x1D, 0x00, 0x0E, 0x00, 0x00, 0x00, 0xBB, 0x75,
+
+        // dwFileFlagsMask, dwFileFlags, dwFileOS, dwFileType
+        0x3F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x04, 0x00, 0x02, 0x00, 0x00, 0x00,
+
+        // dwFileSubtype, dwFileDateMS, dwFileDateLS
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+    ];
+
+    #[test]
+    fn test_parse_file_version() {
+        let mut dll = b"MZ\x90\x00".to_vec();
+
+        // Signature outside of the version resource is ignored
+        dll.extend(FIXED_FILE_INFO_SIGNATURE.to_le_bytes());
+        dll.extend(((1_u32 << 16) | 2).to_le_bytes());
+
+        assert_eq!(parse_file_version(&dll), None);
+
+        dll.extend(VCRUNTIME140_VERSION_INFO);
+
+        assert_eq!(parse_file_version(&dll), Some(Version::new(14, 29, 0)));
+
+        // Misaligned resource
+        let mut misaligned = b"MZ\x90".to_vec();
+
+        misaligned.extend(VCRUNTIME140_VERSION_INFO);
+
+        assert_eq!(parse_file_version(&misaligned), None);
+
+        // Unknown structure version
+        let mut dll = VCRUNTIME140_VERSION_INFO.to_vec();
+
+        dll[44] = 0x02;
+
+        assert_eq!(parse_file_version(&dll), None);
     }
 
-    std::fs::write(reg_file, new_reg)?;
+    const USER_REG: &str = "WINE REGISTRY Version 2
+;; All keys relative to \\\\User\\\\S-1-5-21-0-0-0-1000
 
-    Ok(())
+#arch=win64
+
+[Control Panel\\\\Desktop] 1700000000
+#time=1da0000000000000
+\"FontSmoothing\"=\"2\"
+
+[Software\\\\Wine\\\\DllOverrides] 1700000000
+#time=1da0000000000000
+\"d3d11\"=\"native\"
+\"MSVCP140\"=\"native\"
+
+[Software\\\\Wine\\\\X11 Driver] 1700000000
+#time=1da0000000000000
+\"Decorated\"=\"Y\"
+";
+
+    #[test]
+    fn test_add_dll_overrides() {
+        let reg = add_dll_overrides(USER_REG, &["msvcp140", "msvcp140_1", "ucrtbase"]);
+
+        assert!(reg.contains("\"d3d11\"=\"native\"\n\"MSVCP140\"=\"native\"\n\"msvcp140_1\"=\"native,builtin\"\n\"ucrtbase\"=\"native,builtin\"\n\n[Software"));
+        assert!(!reg.contains("\"msvcp140\"=\"native,builtin\""));
+        assert!(reg.contains("\"Decorated\"=\"Y\"\n"));
+
+        // Already added overrides are not duplicated
+        assert_eq!(add_dll_overrides(&reg, &["msvcp140", "msvcp140_1", "ucrtbase"]), reg);
+    }
+
+    #[test]
+    fn test_add_dll_overrides_crlf() {
+        let reg = add_dll_overrides(&USER_REG.replace('\n', "\r\n"), &["ucrtbase"]);
+
+        assert!(reg.contains("\"MSVCP140\"=\"native\"\r\n\"ucrtbase\"=\"native,builtin\"\r\n\r\n[Software"));
+        assert!(!reg.replace("\r\n", "").contains('\n'));
+    }
+
+    #[test]
+    fn test_add_dll_overrides_new_section() {
+        let reg = add_dll_overrides("WINE REGISTRY Version 2\n\n[Software\\\\Wine] 1700000000\n\"Version\"=\"win10\"\n", &["ucrtbase"]);
+
+        assert!(reg.ends_with("\"Version\"=\"win10\"\n\n[Software\\\\Wine\\\\DllOverrides]\n\"ucrtbase\"=\"native,builtin\"\n\n"));
+    }
 }
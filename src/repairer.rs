@@ -1,8 +1,6 @@
 use std::path::PathBuf;
 use std::collections::HashSet;
 
-use md5::{Md5, Digest};
-
 use super::installer::downloader::{Downloader, DownloadingError};
 
 // {"remoteName": "UnityPlayer.dll", "md5": "8c8c3d845b957e4cb84c662bed44d072", "fileSize": 33466104}
@@ -36,8 +34,8 @@ impl IntegrityFile {
         else {
             tracing::trace!("Comparing hashes");
 
-            match std::fs::read(&file_path) {
-                Ok(hash) => format!("{:x}", Md5::digest(hash)).to_ascii_lowercase() == self.md5.to_ascii_lowercase(),
+            match crate::hash::md5_file(&file_path) {
+                Ok(hash) => hash == self.md5.to_ascii_lowercase(),
                 Err(_) => false
             }
         }
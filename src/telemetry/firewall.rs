@@ -0,0 +1,79 @@
+use std::process::Command;
+
+/// Comment attached to all the iptables rules added by this module
+pub const RULE_COMMENT: &str = "anime-game-core-telemetry";
+
+fn iptables(args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("iptables")
+        .args(args)
+        .output()?;
+
+    if !output.status.success() {
+        anyhow::bail!("Failed to run iptables {}: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Block outgoing connections to the given servers using iptables rules
+///
+/// Domain names are resolved by iptables when the rules are added.
+/// Requires root or `CAP_NET_ADMIN` capability
+#[tracing::instrument(level = "debug")]
+pub fn block_via_iptables(servers: &[&str]) -> anyhow::Result<()> {
+    for server in servers {
+        tracing::debug!("Blocking server: {server}");
+
+        iptables(&["-A", "OUTPUT", "-d", server, "-m", "comment", "--comment", RULE_COMMENT, "-j", "DROP"])?;
+    }
+
+    Ok(())
+}
+
+/// Remove all the iptables rules added by `block_via_iptables`
+///
+/// Rules are identified by the `RULE_COMMENT` comment so user's own rules are not touched
+#[tracing::instrument(level = "debug")]
+pub fn unblock_via_iptables() -> anyhow::Result<()> {
+    let rules = iptables(&["-S", "OUTPUT"])?;
+
+    for rule in get_tagged_rules(&rules) {
+        tracing::debug!("Removing rule: {}", rule.join(" "));
+
+        let mut args = vec!["-D"];
+
+        args.extend(rule);
+
+        iptables(&args)?;
+    }
+
+    Ok(())
+}
+
+/// Get arguments (without the leading `-A`) of the rules tagged by `RULE_COMMENT`
+fn get_tagged_rules(rules: &str) -> Vec<Vec<&str>> {
+    rules.lines()
+        .map(|rule| rule.split_whitespace().collect::<Vec<_>>())
+        .filter(|rule| rule.first() == Some(&"-A"))
+        .filter(|rule| rule.windows(2).any(|args| args == ["--comment", RULE_COMMENT]))
+        .map(|rule| rule[1..].to_vec())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_tagged_rules() {
+        let rules = [
+            "-P OUTPUT ACCEPT",
+            "-A OUTPUT -d 10.0.0.1/32 -j DROP",
+            "-A OUTPUT -d 10.0.0.2/32 -m comment --comment anime-game-core-telemetry -j DROP"
+        ].join("\n");
+
+        assert_eq!(get_tagged_rules(&rules), vec![
+            vec!["OUTPUT", "-d", "10.0.0.2/32", "-m", "comment", "--comment", "anime-game-core-telemetry", "-j", "DROP"]
+        ]);
+    }
+}
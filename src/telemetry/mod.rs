@@ -0,0 +1,147 @@
+use std::path::Path;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+#[cfg(feature = "telemetry-firewall")]
+pub mod firewall;
+
+/// Find telemetry servers blocked by the patch scripts
+///
+/// All the `.sh` and `.bat` files in the patch folder are scanned for
+/// `0.0.0.0 <domain>` hosts file records
+///
+/// ```no_run
+/// use anime_game_core::telemetry;
+///
+/// let servers = telemetry::discover_from_patch("/path/to/patch")
+///     .expect("Failed to read patch folder");
+/// ```
+#[tracing::instrument(level = "debug", skip(patch_folder), fields(patch_folder = ?patch_folder.as_ref()))]
+pub fn discover_from_patch(patch_folder: impl AsRef<Path>) -> anyhow::Result<Vec<String>> {
+    tracing::debug!("Looking for telemetry servers in patch scripts");
+
+    let mut servers = Vec::new();
+
+    for entry in patch_folder.as_ref().read_dir()? {
+        let path = entry?.path();
+
+        let is_script = path.extension()
+            .map(|ext| ext == "sh" || ext == "bat")
+            .unwrap_or(false);
+
+        if !is_script || !path.is_file() {
+            continue;
+        }
+
+        // Scripts may contain non-UTF-8 characters
+        let script = String::from_utf8_lossy(&std::fs::read(&path)?).to_string();
+
+        for server in parse_servers(&script) {
+            if !servers.contains(&server) {
+                servers.push(server);
+            }
+        }
+    }
+
+    servers.sort();
+
+    Ok(servers)
+}
+
+/// Get domains from all the `0.0.0.0 <domain>` records in the text
+fn parse_servers(script: &str) -> Vec<String> {
+    script.match_indices("0.0.0.0 ")
+        .map(|(pos, pattern)| &script[pos + pattern.len()..])
+        .map(|domain| domain.trim_start_matches(' '))
+        .map(|domain| {
+            domain.chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '.' || *c == '-')
+                .collect::<String>()
+        })
+        .filter(|domain| domain.contains('.'))
+        .collect()
+}
+
+/// Merge known telemetry servers with discovered ones, removing duplicates
+pub fn merge_servers<T: AsRef<str>>(known: &[T], discovered: Vec<String>) -> Vec<String> {
+    let mut servers = known.iter()
+        .map(|server| server.as_ref().to_string())
+        .collect::<Vec<_>>();
+
+    for server in discovered {
+        if !servers.contains(&server) {
+            servers.push(server);
+        }
+    }
+
+    servers
+}
+
+/// Check whether the domain is not reachable by trying to connect to its 443 port
+///
+/// Unlike `check_domain::available` this also covers blocking methods
+/// which don't affect DNS resolution, e.g. firewall rules
+#[tracing::instrument(level = "debug")]
+pub fn is_truly_blocked(domain: &str) -> anyhow::Result<bool> {
+    let addresses = match (domain, 443).to_socket_addrs() {
+        Ok(addresses) => addresses,
+
+        // Domain can't be resolved
+        Err(_) => return Ok(true)
+    };
+
+    for address in addresses {
+        if address.ip().is_loopback() || address.ip().is_unspecified() {
+            continue;
+        }
+
+        if TcpStream::connect_timeout(&address, Duration::from_millis(500)).is_ok() {
+            tracing::warn!("Server is reachable: {domain} ({address})");
+
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_servers() {
+        let script = r#"
+            #!/bin/sh
+
+            echo "0.0.0.0 log-upload-os.example.com" >> /etc/hosts
+            echo "0.0.0.0   overseauspider.example.com" >> /etc/hosts
+            echo 0.0.0.0 sg-public-data-api.example.com>>C:\Windows\System32\drivers\etc\hosts
+            echo "0.0.0.0" >> /dev/null
+        "#;
+
+        assert_eq!(parse_servers(script), vec![
+            String::from("log-upload-os.example.com"),
+            String::from("overseauspider.example.com"),
+            String::from("sg-public-data-api.example.com")
+        ]);
+    }
+
+    #[test]
+    fn test_merge_servers() {
+        let servers = merge_servers(&["a.example.com", "b.example.com"], vec![
+            String::from("b.example.com"),
+            String::from("c.example.com")
+        ]);
+
+        assert_eq!(servers, vec!["a.example.com", "b.example.com", "c.example.com"]);
+    }
+
+    #[test]
+    fn test_is_truly_blocked() -> anyhow::Result<()> {
+        assert!(is_truly_blocked("127.0.0.1")?);
+        assert!(is_truly_blocked("0.0.0.0")?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+lazy_static::lazy_static! {
+    static ref MOCKS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+/// Canned API responses returned instead of sending network requests
+pub struct MockApi;
+
+impl MockApi {
+    /// Return given JSON for all the API requests to this url
+    pub fn register(url: &str, response_json: &str) {
+        if let Ok(mut mocks) = MOCKS.lock() {
+            mocks.insert(url.to_string(), response_json.to_string());
+        }
+    }
+
+    /// Remove registered response
+    pub fn unregister(url: &str) {
+        if let Ok(mut mocks) = MOCKS.lock() {
+            mocks.remove(url);
+        }
+    }
+
+    /// Remove all the registered responses
+    pub fn clear() {
+        if let Ok(mut mocks) = MOCKS.lock() {
+            mocks.clear();
+        }
+    }
+
+    /// Get registered response
+    pub fn get(url: &str) -> Option<String> {
+        MOCKS.lock().ok()?.get(url).cloned()
+    }
+}
@@ -16,6 +16,104 @@ pub trait GameExt {
         self.path().exists()
     }
 
+    /// Name of the game's executable, e.g. `game.exe`
+    ///
+    /// `None` if it's unknown for this game
+    fn executable_name(&self) -> Option<&str> {
+        None
+    }
+
+    /// Checks if the game is running
+    ///
+    /// Looks for processes which executable or first command line argument
+    /// has the game's executable name. Wine processes are matched by their
+    /// first argument, e.g. `C:\path\to\game\game.exe`.
+    ///
+    /// Always `false` if the executable name is unknown
+    fn is_running(&self) -> bool {
+        match self.executable_name() {
+            Some(name) => is_running_with_name(name),
+            None => false
+        }
+    }
+
     fn get_latest_version(edition: Self::Edition) -> anyhow::Result<Version>;
     fn get_version(&self) -> anyhow::Result<Version>;
 }
+
+/// Check if there's a process with the given executable name
+fn is_running_with_name(name: &str) -> bool {
+    let Ok(processes) = std::fs::read_dir("/proc") else {
+        return false;
+    };
+
+    processes.flatten().any(|process| {
+        let process = process.path();
+
+        let exe = std::fs::read_link(process.join("exe")).ok();
+        let cmdline = std::fs::read(process.join("cmdline")).unwrap_or_default();
+
+        is_process_with_name(exe.as_deref(), &cmdline, name)
+    })
+}
+
+/// Check if the process executable or its first argument has the given name
+fn is_process_with_name(exe: Option<&Path>, cmdline: &[u8], name: &str) -> bool {
+    let exe_matches = exe.and_then(|exe| exe.file_name())
+        .map(|exe| exe.to_string_lossy().eq_ignore_ascii_case(name))
+        .unwrap_or(false);
+
+    if exe_matches {
+        return true;
+    }
+
+    let Some(first_arg) = cmdline.split(|byte| *byte == 0).next() else {
+        return false;
+    };
+
+    // Windows paths are not case sensitive and use backslashes
+    String::from_utf8_lossy(first_arg)
+        .rsplit(['/', '\\'])
+        .next()
+        .map(|arg| arg.eq_ignore_ascii_case(name))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_process_with_name() {
+        // Wine process
+        assert!(is_process_with_name(
+            Some(Path::new("/usr/bin/wine64-preloader")),
+            b"C:\\Program Files\\Game\\Game.exe\0-screen-fullscreen\0",
+            "game.exe"
+        ));
+
+        // Native process
+        assert!(is_process_with_name(Some(Path::new("/games/game/game.exe")), b"", "game.exe"));
+
+        // Processes working with the game files
+        assert!(!is_process_with_name(
+            Some(Path::new("/usr/bin/chmod")),
+            b"chmod\0-R\0a+rw\0/games/game/game.exe\0",
+            "game.exe"
+        ));
+
+        assert!(!is_process_with_name(
+            Some(Path::new("/usr/bin/rsync")),
+            b"rsync\0-a\0Z:\\games\\game\\game.exe\0/backup\0",
+            "game.exe"
+        ));
+    }
+
+    #[test]
+    fn test_is_running_with_name() {
+        let exe = std::env::current_exe().unwrap();
+
+        assert!(is_running_with_name(&exe.file_name().unwrap().to_string_lossy()));
+        assert!(!is_running_with_name("anime-game-core-missing-process.exe"));
+    }
+}
@@ -38,6 +38,34 @@ impl Version {
         None
     }
 
+    /// Get `Version` from the partial or non-standard string
+    ///
+    /// Missing components are filled with zeros, pre-release and build
+    /// labels (`-beta`, `+build`) and components after the third are ignored
+    ///
+    /// ```
+    /// use anime_game_core::prelude::Version;
+    ///
+    /// assert_eq!(Version::from_str_lenient("5"), Some(Version::new(5, 0, 0)));
+    /// assert_eq!(Version::from_str_lenient("5.0.0-beta"), Some(Version::new(5, 0, 0)));
+    /// ```
+    pub fn from_str_lenient<T: AsRef<str>>(str: T) -> Option<Self> {
+        let str = str.as_ref().trim();
+
+        let str = match str.find(['-', '+']) {
+            Some(pos) => &str[..pos],
+            None => str
+        };
+
+        let mut version = [0; 3];
+
+        for (i, part) in str.split('.').take(3).enumerate() {
+            version[i] = part.parse().ok()?;
+        }
+
+        Some(Self { version })
+    }
+
     /// Converts `Version` struct to plain format (e.g. "123")
     /// 
     /// ```
@@ -110,7 +138,7 @@ impl PartialOrd<&str> for Version {
     fn partial_cmp(&self, other: &&str) -> Option<Ordering> {
         self.to_string()
             .as_str()
-            .partial_cmp(other)
+            .partial_cmp(*other)
     }
 }
 
@@ -120,6 +148,72 @@ impl PartialOrd<Version> for &str {
     }
 }
 
+/// Range of versions. Both bounds are inclusive
+///
+/// ```
+/// use anime_game_core::prelude::*;
+///
+/// let range = VersionRange::between(Version::new(4, 0, 0), Version::new(4, 8, 0));
+///
+/// assert!(range.contains(&Version::new(4, 5, 0)));
+/// assert!(!range.contains(&Version::new(5, 0, 0)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VersionRange {
+    /// Lowest version of the range
+    pub from: Option<Version>,
+
+    /// Highest version of the range
+    pub until: Option<Version>
+}
+
+impl VersionRange {
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    /// Range of all the versions starting from the given one
+    pub fn from(version: Version) -> Self {
+        Self {
+            from: Some(version),
+            until: None
+        }
+    }
+
+    #[inline]
+    /// Range of all the versions up to the given one
+    pub fn until(version: Version) -> Self {
+        Self {
+            from: None,
+            until: Some(version)
+        }
+    }
+
+    #[inline]
+    /// Range of versions between given ones
+    pub fn between(from: Version, until: Version) -> Self {
+        Self {
+            from: Some(from),
+            until: Some(until)
+        }
+    }
+
+    /// Check if the version is within the range
+    pub fn contains(&self, version: &Version) -> bool {
+        if let Some(from) = &self.from {
+            if version < from {
+                return false;
+            }
+        }
+
+        if let Some(until) = &self.until {
+            if version > until {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,6 +263,23 @@ mod tests {
         assert_eq!(Version::from_str("0.0."), None);
     }
 
+    #[test]
+    fn test_version_from_str_lenient() {
+        assert_eq!(Version::from_str_lenient("5"), Some(Version::new(5, 0, 0)));
+        assert_eq!(Version::from_str_lenient("5.1"), Some(Version::new(5, 1, 0)));
+        assert_eq!(Version::from_str_lenient("5.1.2"), Some(Version::new(5, 1, 2)));
+        assert_eq!(Version::from_str_lenient("5.1.2.3"), Some(Version::new(5, 1, 2)));
+        assert_eq!(Version::from_str_lenient("5.0.0-beta"), Some(Version::new(5, 0, 0)));
+        assert_eq!(Version::from_str_lenient("5.0-rc.1"), Some(Version::new(5, 0, 0)));
+
+        assert_eq!(Version::from_str_lenient(""), None);
+        assert_eq!(Version::from_str_lenient("5..0"), None);
+        assert_eq!(Version::from_str_lenient("beta"), None);
+
+        // Strict parsing is not affected
+        assert_eq!(Version::from_str("5.0"), None);
+    }
+
     #[test]
     #[allow(clippy::cmp_owned)]
     fn test_version_comparison() {
@@ -190,4 +301,27 @@ mod tests {
         assert!(Version::new(1, 0, 0) == String::from("1.0.0"));
         assert!(String::from("1.0.0") == Version::new(1, 0, 0));
     }
+
+    #[test]
+    fn test_version_range() {
+        let range = VersionRange::from(Version::new(4, 0, 0));
+
+        assert!(range.contains(&Version::new(4, 0, 0)));
+        assert!(range.contains(&Version::new(5, 0, 0)));
+        assert!(!range.contains(&Version::new(3, 8, 0)));
+
+        let range = VersionRange::until(Version::new(4, 0, 0));
+
+        assert!(range.contains(&Version::new(4, 0, 0)));
+        assert!(range.contains(&Version::new(3, 8, 0)));
+        assert!(!range.contains(&Version::new(4, 0, 1)));
+
+        let range = VersionRange::between(Version::new(4, 0, 0), Version::new(4, 8, 0));
+
+        assert!(range.contains(&Version::new(4, 0, 0)));
+        assert!(range.contains(&Version::new(4, 5, 0)));
+        assert!(range.contains(&Version::new(4, 8, 0)));
+        assert!(!range.contains(&Version::new(3, 8, 0)));
+        assert!(!range.contains(&Version::new(5, 0, 0)));
+    }
 }